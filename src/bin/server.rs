@@ -0,0 +1,179 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use link_converter::{server, Converter, MemoryCache};
+use tokio::net::TcpListener;
+
+const USAGE: &str = "\
+Usage: link-converter-server [OPTIONS]
+
+Serves link conversions as a JSON API:
+  POST /convert       {\"url\", \"agent\"?}   Converts a single link
+  POST /convert/bulk  {\"text\", \"agent\"?}  Converts every link found in a text
+  POST /rewrite       {\"text\", \"mode\"?}   Rewrites the links of a text, `replace` or `annotate`
+
+Stops on Ctrl-C or SIGTERM once the in-flight requests are answered.
+
+Options:
+  -b, --bind <ADDR>       Address to listen on [default: 127.0.0.1:3000]
+  -r, --rules <PATH>      Loads agent rules from a `.toml` or `.json` file, can be repeated
+  -c, --cache <CAPACITY>  Caches the network conversions of up to CAPACITY links in memory
+  -h, --help              Prints this help";
+
+const DEFAULT_BIND: &str = "127.0.0.1:3000";
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    bind: SocketAddr,
+    rules: Vec<PathBuf>,
+    /// Capacity of the memory cache, no cache if `None`.
+    cache: Option<usize>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("link-converter-server: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(options).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("link-converter-server: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The server options, `None` if help was requested.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut bind = DEFAULT_BIND.to_string();
+    let mut rules = Vec::new();
+    let mut cache = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("{name} requires a value"))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-b" | "--bind" => bind = value(&arg)?,
+            "-r" | "--rules" => rules.push(value(&arg)?.into()),
+            "-c" | "--cache" => {
+                let capacity = value(&arg)?;
+                match capacity.parse() {
+                    Ok(capacity) if capacity > 0 => cache = Some(capacity),
+                    _ => return Err(format!("invalid cache capacity {capacity}")),
+                }
+            }
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+
+    let bind = bind
+        .parse()
+        .map_err(|e| format!("invalid bind address {bind}: {e}"))?;
+
+    Ok(Some(Options { bind, rules, cache }))
+}
+
+async fn run(options: Options) -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = Converter::builder();
+    for path in options.rules {
+        builder = builder.rules_file(path);
+    }
+    if let Some(capacity) = options.cache {
+        builder = builder.cache(MemoryCache::new(capacity));
+    }
+    let converter = Arc::new(builder.build()?);
+
+    let listener = TcpListener::bind(options.bind).await?;
+    eprintln!(
+        "link-converter-server: listening on {}",
+        listener.local_addr()?
+    );
+
+    server::serve(listener, converter, shutdown_signal()).await?;
+
+    Ok(())
+}
+
+/// Completes on Ctrl-C, or on SIGTERM on Unix.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => _ = terminate.recv().await,
+            Err(_) => std::future::pending().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> core::result::Result<Option<Options>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() -> Result<()> {
+        // -- Exec
+        let options = parse(&["-r", "agents.toml", "--cache", "1024", "-b", "0.0.0.0:8080"])?;
+
+        // -- Check
+        let expected = Options {
+            bind: "0.0.0.0:8080".parse()?,
+            rules: vec![PathBuf::from("agents.toml")],
+            cache: Some(1024),
+        };
+        assert_eq!(options, Some(expected));
+        assert_eq!(parse(&["--help"])?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_parse_args() {
+        // -- Check
+        assert_eq!(parse(&["--rules"]), Err("--rules requires a value".into()));
+        assert_eq!(
+            parse(&["--cache", "0"]),
+            Err("invalid cache capacity 0".into())
+        );
+    }
+}
+
+// endregion: --- Tests
@@ -0,0 +1,187 @@
+use std::path::Path;
+
+use url::Url;
+
+#[cfg(feature = "network")]
+use crate::builder;
+use crate::converters::{self, SyncLinkConverter};
+use crate::{
+    agent_link, bulk_entries, extract, raw_agent_link, register_rules, rewrite_converted,
+    to_agent_links, unique_links, ConversionResult, ConverterBuilder, Error, Product, Result,
+    RewriteMode, RewrittenText, RulesFormat,
+};
+
+/// Synchronous counterpart of the [`crate::Converter`], converting links on the calling thread.
+///
+/// Holds the same converters, with the network ones backed by a `reqwest::blocking::Client`.
+/// Offline conversions run without any async runtime, and without the `network` feature
+/// only offline converters are available.
+#[derive(Debug)]
+pub struct BlockingConverter {
+    pub(crate) converters: Vec<Box<dyn SyncLinkConverter>>,
+}
+
+impl BlockingConverter {
+    /// Creates a new BlockingConverter with the built-in converters and default HTTP client
+    /// settings.
+    ///
+    /// # Errors
+    /// Returns `Error` if the underlying HTTP client fails to build.
+    pub fn new() -> Result<Self> {
+        ConverterBuilder::new().build_blocking()
+    }
+
+    /// Creates a new BlockingConverter using a pre-configured HTTP client.
+    ///
+    /// Its suggested to set a redirect Policy.
+    #[cfg(feature = "network")]
+    pub fn from_client(client: reqwest::blocking::Client) -> Self {
+        Self {
+            converters: builder::builtin_blocking_converters(std::sync::Arc::new(client)),
+        }
+    }
+
+    /// Names of the registered converters, in the order they are tried.
+    pub fn names(&self) -> Vec<&str> {
+        self.converters
+            .iter()
+            .map(|converter| converter.name())
+            .collect()
+    }
+
+    /// Loads agent rules from a `.toml` or `.json` file, see [`crate::Converter::load_rules`].
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidRulesFile` or `Error::InvalidRule` if the file fails validation.
+    pub fn load_rules(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let rules = converters::rule_file::load_rules(path.as_ref())?;
+        register_rules(&mut self.converters, rules);
+
+        Ok(())
+    }
+
+    /// Loads agent rules from the content of a rules file, see [`crate::Converter::load_rules`].
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidRulesFile` or `Error::InvalidRule` if the content fails validation.
+    pub fn load_rules_str(&mut self, content: &str, format: RulesFormat) -> Result<()> {
+        let rules = converters::rule_file::parse_rules(content, format)?;
+        register_rules(&mut self.converters, rules);
+
+        Ok(())
+    }
+
+    /// Converts a single URL using the first applicable converter.
+    ///
+    /// # Errors
+    /// Returns `Error::UnsupportedHost` if no registered converter can handle the URL.
+    pub fn convert_one(&self, url: Url) -> Result<Product> {
+        self.convert_with_converter(url).1
+    }
+
+    /// Name of the converter [`BlockingConverter::convert_one`] uses for the URL, `None` if no
+    /// registered converter supports it.
+    pub fn converter_for(&self, url: &Url) -> Option<&str> {
+        self.converters
+            .iter()
+            .find(|c| c.can_convert(url))
+            .map(|converter| converter.name())
+    }
+
+    fn convert_with_converter(&self, url: Url) -> (Option<&str>, Result<Product>) {
+        let Some(converter) = self.converters.iter().find(|c| c.can_convert(&url)) else {
+            let error = Error::UnsupportedHost {
+                url: url.to_string(),
+            };
+            return (None, Err(error));
+        };
+
+        (Some(converter.name()), converter.convert(url))
+    }
+
+    /// Builds the link of the named agent for a product, see [`crate::Converter::agent_link`].
+    ///
+    /// # Errors
+    /// Returns `Error::UnknownAgent` if no registered converter has that name, or
+    /// `Error::UnsupportedMarketplace` if the agent cannot link to the product's marketplace.
+    pub fn agent_link(&self, product: &Product, agent: &str) -> Result<Url> {
+        agent_link(&self.converters, product, agent)
+    }
+
+    /// Converts a raw marketplace link into the named agent's link, see
+    /// [`crate::Converter::to_agent`].
+    ///
+    /// # Errors
+    /// Returns `Error::UnsupportedHost` if the URL is not a raw marketplace link,
+    /// otherwise see [`BlockingConverter::agent_link`].
+    pub fn to_agent(&self, raw_url: Url, agent: &str) -> Result<Url> {
+        raw_agent_link(&self.converters, raw_url, agent)
+    }
+
+    /// Converts any supported link into the named agent's link.
+    ///
+    /// # Errors
+    /// See [`BlockingConverter::convert_one`] and [`BlockingConverter::agent_link`].
+    pub fn convert_to_agent(&self, url: Url, agent: &str) -> Result<Url> {
+        let product = self.convert_one(url)?;
+
+        self.agent_link(&product, agent)
+    }
+
+    /// Processes text content to find and convert all links, see [`crate::Converter::convert_bulk`].
+    ///
+    /// Links are converted one after the other; identical links are converted only once.
+    pub fn convert_bulk(&self, text: &str) -> Result<ConversionResult> {
+        let links = extract::find_links(text);
+
+        let (unique, positions) = unique_links(&links);
+        let conversions: Vec<_> = unique
+            .into_iter()
+            .map(|url| match Url::parse(url) {
+                Ok(url) => self.convert_with_converter(url),
+                Err(e) => (None, Err(e.into())),
+            })
+            .collect();
+        let entries = bulk_entries(links, &positions, &conversions);
+
+        Ok(ConversionResult { entries })
+    }
+
+    /// Processes text content like [`BlockingConverter::convert_bulk`], converting every URL
+    /// into the named agent's link.
+    pub fn convert_bulk_to_agent(&self, text: &str, agent: &str) -> Result<ConversionResult<Url>> {
+        let converted = self.convert_bulk(text)?;
+
+        Ok(to_agent_links(&self.converters, converted, agent))
+    }
+
+    /// Rewrites the text with its convertible links, see [`crate::Converter::rewrite_text`].
+    pub fn rewrite_text(&self, text: &str, mode: RewriteMode) -> RewrittenText {
+        rewrite_converted(text, mode, self.convert_bulk(text))
+    }
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>;
+
+    use super::*;
+
+    #[test]
+    fn test_blocking_shares_converter_set() -> Result<()> {
+        // -- Exec
+        let converter = ConverterBuilder::new()
+            .enable_only(["cnfans", "mobile_intl_taobao"])
+            .build_blocking()?;
+
+        // -- Check
+        assert_eq!(converter.names(), ["mobile_intl_taobao", "cnfans"]);
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
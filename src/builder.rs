@@ -0,0 +1,553 @@
+use std::path::PathBuf;
+
+use crate::converters::{self, SyncLinkConverter};
+use crate::{BlockingConverter, Error, Result, RulesFormat};
+
+#[cfg(feature = "network")]
+use {
+    crate::converters::{async_trait, LinkConverter},
+    crate::rate_limit::RateLimiter,
+    crate::{
+        BlockingFetcher, ConversionCache, Converter, Fetcher, Product, RateLimit, RetryPolicy,
+        DEFAULT_CONCURRENCY, DEFAULT_TIMEOUT,
+    },
+    reqwest::{redirect::Policy, Client},
+    std::collections::HashMap,
+    std::sync::Arc,
+    std::time::Duration,
+    tokio::sync::Semaphore,
+    url::Url,
+};
+
+/// Names of the built-in converters, in their default order. The network converters
+/// come first and are only built in with the `network` feature.
+pub const BUILTIN_CONVERTERS: &[&str] = &[
+    #[cfg(feature = "network")]
+    "you_shop_10",
+    #[cfg(feature = "network")]
+    "mobile_taobao",
+    "mobile_intl_taobao",
+    "marketplace",
+    "cssbuy",
+    "lovegobuy",
+    "mulebuy",
+    "ootdbuy",
+    "cnfans",
+    "orientdig",
+    "oopbuy",
+    "joyabuy",
+    "acbuy",
+];
+
+#[derive(Debug)]
+enum Entry {
+    Builtin(String),
+    #[cfg(feature = "network")]
+    Custom(Box<dyn LinkConverter>),
+    CustomSync(Box<dyn SyncLinkConverter>),
+}
+
+impl Entry {
+    fn name(&self) -> &str {
+        match self {
+            Entry::Builtin(name) => name,
+            #[cfg(feature = "network")]
+            Entry::Custom(converter) => converter.name(),
+            Entry::CustomSync(converter) => converter.name(),
+        }
+    }
+}
+
+/// A boxed synchronous converter, used by the async [`Converter`].
+///
+/// Network conversions run on tokio's blocking threads, so they never stall the runtime
+/// and their timeouts apply.
+#[cfg(feature = "network")]
+#[derive(Debug)]
+struct SyncAdapter(Arc<dyn SyncLinkConverter>);
+
+#[cfg(feature = "network")]
+#[async_trait]
+impl LinkConverter for SyncAdapter {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn can_convert(&self, url: &Url) -> bool {
+        self.0.can_convert(url)
+    }
+
+    async fn convert(&self, url: Url) -> Result<Product> {
+        if !self.0.is_network() {
+            return self.0.convert(url);
+        }
+
+        let converter = self.0.clone();
+        let input = url.to_string();
+        match tokio::task::spawn_blocking(move || converter.convert(url)).await {
+            Ok(converted) => converted,
+            Err(e) => match e.try_into_panic() {
+                Ok(panic) => std::panic::resume_unwind(panic),
+                // The runtime is shutting down.
+                Err(e) => Err(Error::Http {
+                    url: input,
+                    converter: self.0.name().to_string(),
+                    message: e.to_string(),
+                }),
+            },
+        }
+    }
+
+    fn is_network(&self) -> bool {
+        self.0.is_network()
+    }
+
+    fn agent_link(&self, product: &Product) -> Option<Url> {
+        self.0.agent_link(product)
+    }
+}
+
+#[derive(Debug)]
+enum RulesSource {
+    File(PathBuf),
+    Str(String, RulesFormat),
+}
+
+/// Settings of the network converters and the async [`Converter`].
+#[cfg(feature = "network")]
+#[derive(Debug)]
+struct NetworkOptions {
+    fetcher: Option<Arc<dyn Fetcher>>,
+    blocking_fetcher: Option<Arc<dyn BlockingFetcher>>,
+    concurrency: usize,
+    cache: Option<Box<dyn ConversionCache>>,
+    retry: RetryPolicy,
+    timeout: Duration,
+    converter_timeouts: HashMap<String, Duration>,
+    bulk_deadline: Option<Duration>,
+    rate_limit: Option<RateLimit>,
+    host_rate_limits: HashMap<String, RateLimit>,
+}
+
+#[cfg(feature = "network")]
+impl Default for NetworkOptions {
+    fn default() -> Self {
+        Self {
+            fetcher: None,
+            blocking_fetcher: None,
+            concurrency: DEFAULT_CONCURRENCY,
+            cache: None,
+            retry: RetryPolicy::default(),
+            timeout: DEFAULT_TIMEOUT,
+            converter_timeouts: HashMap::new(),
+            bulk_deadline: None,
+            rate_limit: None,
+            host_rate_limits: HashMap::new(),
+        }
+    }
+}
+
+/// Builder for a [`crate::Converter`] or a [`BlockingConverter`] with a custom set of converters.
+///
+/// Starts with all [`BUILTIN_CONVERTERS`]; converters are tried in order and the first
+/// one able to handle a URL converts it.
+#[derive(Debug)]
+pub struct ConverterBuilder {
+    entries: Vec<Entry>,
+    rules: Vec<RulesSource>,
+    unknown_names: Vec<String>,
+    #[cfg(feature = "network")]
+    network: NetworkOptions,
+}
+
+impl Default for ConverterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConverterBuilder {
+    pub fn new() -> Self {
+        Self {
+            entries: BUILTIN_CONVERTERS
+                .iter()
+                .map(|name| Entry::Builtin(name.to_string()))
+                .collect(),
+            rules: Vec::new(),
+            unknown_names: Vec::new(),
+            #[cfg(feature = "network")]
+            network: NetworkOptions::default(),
+        }
+    }
+
+    /// Registers a synchronous converter after the current ones, available to both the
+    /// [`crate::Converter`] and the [`BlockingConverter`].
+    pub fn register_sync(mut self, converter: impl SyncLinkConverter + 'static) -> Self {
+        self.entries.push(Entry::CustomSync(Box::new(converter)));
+        self
+    }
+
+    /// Removes the converter with the given name.
+    pub fn remove(mut self, name: &str) -> Self {
+        self.entries.retain(|entry| entry.name() != name);
+        self
+    }
+
+    /// Keeps only the named built-in converters; custom converters are kept.
+    ///
+    /// Names that are not in [`BUILTIN_CONVERTERS`] make building fail.
+    pub fn enable_only<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        let names: Vec<&str> = names.into_iter().collect();
+        for name in &names {
+            if !BUILTIN_CONVERTERS.contains(name) {
+                self.unknown_names.push(name.to_string());
+            }
+        }
+        self.entries.retain(|entry| match entry {
+            Entry::Builtin(name) => names.contains(&name.as_str()),
+            _ => true,
+        });
+        self
+    }
+
+    /// Moves the named converters to the front, in the given order.
+    ///
+    /// Names that are not registered at this point, e.g. converters registered later or
+    /// loaded from rules, make building fail.
+    pub fn prioritize<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut prioritized = Vec::new();
+        for name in names {
+            match self.entries.iter().position(|entry| entry.name() == name) {
+                Some(index) => prioritized.push(self.entries.remove(index)),
+                None => self.unknown_names.push(name.to_string()),
+            }
+        }
+        prioritized.append(&mut self.entries);
+        self.entries = prioritized;
+        self
+    }
+
+    /// Loads agent rules from a file when building, see [`BlockingConverter::load_rules`].
+    pub fn rules_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.rules.push(RulesSource::File(path.into()));
+        self
+    }
+
+    /// Loads agent rules from the content of a rules file when building.
+    pub fn rules_str(mut self, content: impl Into<String>, format: RulesFormat) -> Self {
+        self.rules.push(RulesSource::Str(content.into(), format));
+        self
+    }
+
+    /// Builds a [`BlockingConverter`] with the same converters.
+    ///
+    /// Concurrency, cache, retry and rate limit settings only apply to the async
+    /// [`crate::Converter`]; the timeout bounds each request of the default blocking client.
+    ///
+    /// # Errors
+    /// Returns `Error::UnknownConverter` if an unknown converter was enabled or prioritized,
+    /// `Error::AsyncOnlyConverter` if a converter was registered with
+    /// `ConverterBuilder::register`, or `Error` if the default HTTP client fails to build
+    /// or a rules source is invalid.
+    pub fn build_blocking(self) -> Result<BlockingConverter> {
+        if let Some(name) = self.unknown_names.into_iter().next() {
+            return Err(Error::UnknownConverter { name });
+        }
+
+        #[cfg(feature = "network")]
+        let mut builtins = {
+            let fetcher = match self.network.blocking_fetcher {
+                Some(fetcher) => fetcher,
+                None => Arc::new(
+                    reqwest::blocking::Client::builder()
+                        .redirect(Policy::limited(10))
+                        .timeout(self.network.timeout)
+                        .build()?,
+                ),
+            };
+            builtin_blocking_converters(fetcher)
+        };
+        #[cfg(not(feature = "network"))]
+        let mut builtins = offline_converters();
+
+        let converters = self
+            .entries
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Entry::Builtin(name) => builtins
+                    .iter()
+                    .position(|converter| converter.name() == name)
+                    .map(|index| Ok(builtins.swap_remove(index))),
+                #[cfg(feature = "network")]
+                Entry::Custom(converter) => Some(Err(Error::AsyncOnlyConverter {
+                    name: converter.name().to_string(),
+                })),
+                Entry::CustomSync(converter) => Some(Ok(converter)),
+            })
+            .collect::<Result<_>>()?;
+
+        let mut converter = BlockingConverter { converters };
+        for source in self.rules {
+            match source {
+                RulesSource::File(path) => converter.load_rules(path)?,
+                RulesSource::Str(content, format) => converter.load_rules_str(&content, format)?,
+            }
+        }
+
+        Ok(converter)
+    }
+}
+
+#[cfg(feature = "network")]
+impl ConverterBuilder {
+    /// Sets the HTTP client used by network converters.
+    ///
+    /// Its suggested to set a redirect Policy.
+    pub fn client(self, client: Client) -> Self {
+        self.fetcher(client)
+    }
+
+    /// Sets the HTTP client used by network converters of a [`BlockingConverter`].
+    pub fn blocking_client(self, client: reqwest::blocking::Client) -> Self {
+        self.blocking_fetcher(client)
+    }
+
+    /// Sets the transport of the network converters, replacing the HTTP client.
+    pub fn fetcher(mut self, fetcher: impl Fetcher + 'static) -> Self {
+        self.network.fetcher = Some(Arc::new(fetcher));
+        self
+    }
+
+    /// Sets the transport of the network converters of a [`BlockingConverter`],
+    /// replacing its HTTP client.
+    pub fn blocking_fetcher(mut self, fetcher: impl BlockingFetcher + 'static) -> Self {
+        self.network.blocking_fetcher = Some(Arc::new(fetcher));
+        self
+    }
+
+    /// Registers a converter after the current ones.
+    ///
+    /// Such converters are only available to the async [`Converter`], see
+    /// [`ConverterBuilder::register_sync`] for converters usable by both.
+    pub fn register(mut self, converter: impl LinkConverter + 'static) -> Self {
+        self.entries.push(Entry::Custom(Box::new(converter)));
+        self
+    }
+
+    /// Registers a converter before the current ones, so it takes precedence.
+    pub fn register_first(mut self, converter: impl LinkConverter + 'static) -> Self {
+        self.entries.insert(0, Entry::Custom(Box::new(converter)));
+        self
+    }
+
+    /// Sets how many network conversions may run at the same time, defaults to
+    /// [`DEFAULT_CONCURRENCY`]. Offline conversions are never limited.
+    ///
+    /// # Panics
+    /// Panics if `limit` is zero.
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "concurrency limit must be at least 1");
+        self.network.concurrency = limit;
+        self
+    }
+
+    /// Sets the cache consulted before calling a network converter, e.g. a [`crate::MemoryCache`].
+    /// No cache is used by default.
+    ///
+    /// Pass an `Arc` of the cache to keep using it, e.g. to sweep a [`crate::DiskCache`].
+    pub fn cache(mut self, cache: impl ConversionCache + 'static) -> Self {
+        self.network.cache = Some(Box::new(cache));
+        self
+    }
+
+    /// Sets how failed network conversions are retried, defaults to [`RetryPolicy::default`].
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.network.retry = policy;
+        self
+    }
+
+    /// Sets how long a network converter gets for one conversion attempt, defaults to
+    /// [`DEFAULT_TIMEOUT`]. Timed out attempts fail with `Error::Timeout` and are retried
+    /// according to the retry policy.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.network.timeout = timeout;
+        self
+    }
+
+    /// Overrides the timeout of the named converter.
+    pub fn converter_timeout(mut self, name: impl Into<String>, timeout: Duration) -> Self {
+        self.network.converter_timeouts.insert(name.into(), timeout);
+        self
+    }
+
+    /// Sets a deadline for whole bulk conversions, after which links still converting are
+    /// reported as `Error::Timeout`. No deadline is set by default.
+    pub fn bulk_deadline(mut self, deadline: Duration) -> Self {
+        self.network.bulk_deadline = Some(deadline);
+        self
+    }
+
+    /// Limits the requests network converters send to each upstream host. Requests over
+    /// the limit wait for their turn instead of failing. No limit is set by default.
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.network.rate_limit = Some(limit);
+        self
+    }
+
+    /// Overrides the rate limit of an upstream host, e.g. `"m.tb.cn"`.
+    pub fn host_rate_limit(mut self, host: impl Into<String>, limit: RateLimit) -> Self {
+        self.network.host_rate_limits.insert(host.into(), limit);
+        self
+    }
+
+    /// Builds the Converter.
+    ///
+    /// # Errors
+    /// Returns `Error::UnknownConverter` if an unknown converter was enabled or prioritized,
+    /// or `Error` if the default HTTP client fails to build or a rules source is invalid.
+    pub fn build(self) -> Result<Converter> {
+        if let Some(name) = self.unknown_names.into_iter().next() {
+            return Err(Error::UnknownConverter { name });
+        }
+
+        let network = self.network;
+        let fetcher = match network.fetcher {
+            Some(fetcher) => fetcher,
+            None => Arc::new(Client::builder().redirect(Policy::limited(10)).build()?),
+        };
+
+        let mut builtins = builtin_converters(fetcher);
+        let converters = self
+            .entries
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Entry::Builtin(name) => builtins
+                    .iter()
+                    .position(|converter| converter.name() == name)
+                    .map(|index| builtins.swap_remove(index)),
+                Entry::Custom(converter) => Some(converter),
+                Entry::CustomSync(converter) => Some(Box::new(SyncAdapter(converter.into()))),
+            })
+            .collect();
+
+        let mut converter = Converter {
+            converters,
+            network_permits: Semaphore::new(network.concurrency),
+            cache: network.cache,
+            retry: network.retry,
+            timeout: network.timeout,
+            converter_timeouts: network.converter_timeouts,
+            bulk_deadline: network.bulk_deadline,
+            rate_limiter: RateLimiter::new(network.rate_limit, network.host_rate_limits),
+        };
+        for source in self.rules {
+            match source {
+                RulesSource::File(path) => converter.load_rules(path)?,
+                RulesSource::Str(content, format) => converter.load_rules_str(&content, format)?,
+            }
+        }
+
+        Ok(converter)
+    }
+}
+
+/// Creates the built-in converters, in the order of [`BUILTIN_CONVERTERS`].
+#[cfg(feature = "network")]
+pub(crate) fn builtin_converters(fetcher: Arc<dyn Fetcher>) -> Vec<Box<dyn LinkConverter>> {
+    let mut converters: Vec<Box<dyn LinkConverter>> = vec![
+        Box::new(converters::you_shop_10::YouShop10::new(fetcher.clone())),
+        Box::new(converters::mobile_taobao::MobileTaobao::new(fetcher)),
+    ];
+    for converter in offline_converters() {
+        converters.push(Box::new(SyncAdapter(converter.into())));
+    }
+
+    converters
+}
+
+/// Creates the built-in converters of a [`BlockingConverter`], in the order of [`BUILTIN_CONVERTERS`].
+#[cfg(feature = "network")]
+pub(crate) fn builtin_blocking_converters(
+    fetcher: Arc<dyn BlockingFetcher>,
+) -> Vec<Box<dyn SyncLinkConverter>> {
+    let mut converters: Vec<Box<dyn SyncLinkConverter>> = vec![
+        Box::new(converters::you_shop_10::YouShop10::blocking(
+            fetcher.clone(),
+        )),
+        Box::new(converters::mobile_taobao::MobileTaobao::blocking(fetcher)),
+    ];
+    converters.extend(offline_converters());
+
+    converters
+}
+
+/// Creates the built-in converters that make no network requests.
+pub(crate) fn offline_converters() -> Vec<Box<dyn SyncLinkConverter>> {
+    let mut converters: Vec<Box<dyn SyncLinkConverter>> = vec![
+        Box::new(converters::mobile_intl_taobao::MobileIntlTaobao),
+        Box::new(converters::raw::RawMarketplace::new()),
+        Box::new(converters::cssbuy::CSSBuy::new()),
+    ];
+
+    for rule in converters::rule::builtin_rules() {
+        converters.push(Box::new(converters::rule::RuleConverter::new(rule)));
+    }
+
+    converters
+}
+
+// region:    --- Tests
+
+#[cfg(all(test, feature = "network"))]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>;
+
+    use super::*;
+
+    #[test]
+    fn test_builtin_names_match_converters() {
+        // -- Exec
+        let converters = builtin_converters(Arc::new(Client::new()));
+
+        // -- Check
+        let names: Vec<&str> = converters.iter().map(|c| c.name()).collect();
+        assert_eq!(names, BUILTIN_CONVERTERS);
+    }
+
+    #[test]
+    fn test_enable_only_and_prioritize() -> Result<()> {
+        // -- Exec
+        let converter = ConverterBuilder::new()
+            .enable_only(["cnfans", "mulebuy", "cssbuy"])
+            .prioritize(["cnfans"])
+            .build()?;
+
+        // -- Check
+        assert_eq!(converter.names(), ["cnfans", "cssbuy", "mulebuy"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_enable_unknown_builtin() {
+        // -- Exec
+        let actual = ConverterBuilder::new().enable_only(["cnfanz"]).build();
+
+        // -- Check
+        assert!(matches!(actual, Err(crate::Error::UnknownConverter { name }) if name == "cnfanz"));
+    }
+
+    #[test]
+    fn test_fail_prioritize_unknown_converter() {
+        // -- Exec
+        let actual = ConverterBuilder::new()
+            .prioritize(["cnfans", "cnfanz"])
+            .build();
+
+        // -- Check
+        assert!(matches!(actual, Err(crate::Error::UnknownConverter { name }) if name == "cnfanz"));
+    }
+}
+
+// endregion: --- Tests
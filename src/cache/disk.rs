@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::ConversionCache;
+use crate::{Error, Marketplace, Product, Result};
+
+/// Default time a resolution stays in a [`DiskCache`].
+pub const DEFAULT_DISK_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Default number of buffered resolutions that makes a [`DiskCache`] write them.
+const DEFAULT_FLUSH_RECORDS: usize = 64;
+
+/// Default time a resolution stays buffered by a [`DiskCache`] before it is written.
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A resolved link stored by a [`DiskCache`], one JSON object per line of the store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheRecord {
+    /// The normalized input URL.
+    pub url: String,
+    /// Name of the converter that resolved the URL.
+    pub converter: String,
+    pub marketplace: Marketplace,
+    pub id: String,
+    /// Resolution time, in seconds since the Unix epoch.
+    pub resolved_at: u64,
+}
+
+impl CacheRecord {
+    /// The product the URL resolved to.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidProductId` if the record holds an invalid id.
+    pub fn product(&self) -> Result<Product> {
+        Product::new(self.marketplace, &self.id)
+    }
+
+    fn is_expired(&self, ttl: Duration, now: u64) -> bool {
+        self.resolved_at.saturating_add(ttl.as_secs()) <= now
+    }
+}
+
+/// File-backed [`ConversionCache`] persisting resolved links across restarts.
+///
+/// The store is a JSON lines file: new resolutions are buffered in memory and appended
+/// in one write once enough of them are buffered or the oldest has waited long enough,
+/// see [`DiskCache::flush_after`], on [`DiskCache::flush`] and when the cache is dropped.
+/// [`DiskCache::sweep`] rewrites the store without the expired ones. Only successful
+/// conversions are stored, and failing writes are ignored as the cache is best-effort.
+///
+/// To keep calling these methods once the cache is given to a [`crate::ConverterBuilder`],
+/// share it in an `Arc`.
+#[derive(Debug)]
+pub struct DiskCache {
+    path: PathBuf,
+    ttl: Duration,
+    flush_records: usize,
+    flush_interval: Duration,
+    records: Mutex<HashMap<String, CacheRecord>>,
+    pending: Mutex<Pending>,
+}
+
+/// Records inserted since the last write of the store.
+#[derive(Debug, Default)]
+struct Pending {
+    records: Vec<CacheRecord>,
+    /// When the oldest buffered record was inserted.
+    since: Option<Instant>,
+}
+
+impl DiskCache {
+    /// Opens the store at `path`, loading its records. The file is created on the first write.
+    ///
+    /// Malformed lines, e.g. a line cut short by a crash, and records with an invalid
+    /// product id are skipped.
+    ///
+    /// # Errors
+    /// Returns `Error::CacheStore` if the file exists but cannot be read.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut records = HashMap::new();
+
+        match fs::File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    let line = line.map_err(|e| store_error(&path, e))?;
+                    match serde_json::from_str::<CacheRecord>(&line) {
+                        Ok(record) if record.product().is_ok() => merge(&mut records, record),
+                        _ => {}
+                    }
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(store_error(&path, e)),
+        }
+
+        Ok(Self {
+            path,
+            ttl: DEFAULT_DISK_TTL,
+            flush_records: DEFAULT_FLUSH_RECORDS,
+            flush_interval: DEFAULT_FLUSH_INTERVAL,
+            records: Mutex::new(records),
+            pending: Mutex::default(),
+        })
+    }
+
+    /// Sets how long resolutions stay cached, defaults to [`DEFAULT_DISK_TTL`].
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets when buffered resolutions are written: once `records` of them are buffered,
+    /// or on the next insert after the oldest has waited `interval`. Defaults to 64
+    /// records and 5 seconds.
+    ///
+    /// The write is a short append made by the inserting conversion, outside the locks.
+    ///
+    /// # Panics
+    /// Panics if `records` is zero.
+    pub fn flush_after(mut self, records: usize, interval: Duration) -> Self {
+        assert!(records > 0, "flush threshold must be at least 1 record");
+        self.flush_records = records;
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Number of stored URLs, expired ones included until swept.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the unexpired records as JSON lines.
+    ///
+    /// # Errors
+    /// Returns `Error::CacheStore` if writing fails.
+    pub fn export(&self, mut writer: impl Write) -> Result<()> {
+        let now = now();
+        for record in self.lock().values() {
+            if !record.is_expired(self.ttl, now) {
+                writeln!(writer, "{}", to_line(record)).map_err(|e| store_error(&self.path, e))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merges JSON lines records, e.g. from [`DiskCache::export`], into the store. A record
+    /// replaces the stored one for its URL only if it is more recent.
+    ///
+    /// Returns the number of imported records.
+    ///
+    /// # Errors
+    /// Returns `Error::CacheStore` if reading fails or a line is not a valid record, e.g.
+    /// one with an invalid product id, in which case nothing is imported.
+    pub fn import(&self, reader: impl BufRead) -> Result<usize> {
+        let mut imported = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| store_error(&self.path, e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = |reason: String| Error::CacheStore {
+                path: self.path.display().to_string(),
+                reason: format!("invalid record on line {}: {reason}", index + 1),
+            };
+            let record =
+                serde_json::from_str::<CacheRecord>(&line).map_err(|e| invalid(e.to_string()))?;
+            record.product().map_err(|e| invalid(e.to_string()))?;
+            imported.push(record);
+        }
+
+        let count = imported.len();
+        let mut records = self.lock();
+        for record in imported {
+            merge(&mut records, record);
+        }
+        self.write_all(&records)?;
+
+        Ok(count)
+    }
+
+    /// Removes the expired records and compacts the store.
+    ///
+    /// Returns the number of removed records.
+    ///
+    /// # Errors
+    /// Returns `Error::CacheStore` if the store cannot be rewritten.
+    pub fn sweep(&self) -> Result<usize> {
+        let now = now();
+        let mut records = self.lock();
+        let before = records.len();
+        records.retain(|_, record| !record.is_expired(self.ttl, now));
+        self.write_all(&records)?;
+
+        Ok(before - records.len())
+    }
+
+    /// Appends the buffered resolutions to the store.
+    ///
+    /// This is blocking file I/O: from async code, call it through
+    /// `tokio::task::spawn_blocking` rather than on a runtime thread.
+    ///
+    /// # Errors
+    /// Returns `Error::CacheStore` if the store cannot be written, in which case the
+    /// resolutions stay buffered.
+    pub fn flush(&self) -> Result<()> {
+        let pending = std::mem::take(&mut *self.lock_pending());
+        if pending.records.is_empty() {
+            return Ok(());
+        }
+
+        match self.append(&pending.records) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let mut buffered = self.lock_pending();
+                buffered.records.splice(0..0, pending.records);
+                buffered.since = pending.since;
+                Err(store_error(&self.path, e))
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, CacheRecord>> {
+        // The records stay consistent even if a holder panicked.
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_pending(&self) -> MutexGuard<'_, Pending> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Rewrites the store through a temporary file, so a crash never leaves it half written.
+    ///
+    /// The buffered resolutions are part of `records`, so they are written too.
+    fn write_all(&self, records: &HashMap<String, CacheRecord>) -> Result<()> {
+        let content: String = records
+            .values()
+            .map(|record| to_line(record) + "\n")
+            .collect();
+
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, content)
+            .and_then(|_| fs::rename(&tmp, &self.path))
+            .map_err(|e| store_error(&self.path, e))?;
+        *self.lock_pending() = Pending::default();
+
+        Ok(())
+    }
+
+    fn append(&self, records: &[CacheRecord]) -> io::Result<()> {
+        let content: String = records
+            .iter()
+            .map(|record| to_line(record) + "\n")
+            .collect();
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(content.as_bytes())
+    }
+}
+
+impl Drop for DiskCache {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+impl ConversionCache for DiskCache {
+    fn get(&self, url: &str) -> Option<Result<Product>> {
+        let records = self.lock();
+        let record = records.get(url)?;
+        if record.is_expired(self.ttl, now()) {
+            return None;
+        }
+
+        Some(record.product())
+    }
+
+    fn insert(&self, url: &str, converter: &str, outcome: &Result<Product>) {
+        let Ok(product) = outcome else {
+            return;
+        };
+
+        let record = CacheRecord {
+            url: url.to_string(),
+            converter: converter.to_string(),
+            marketplace: product.marketplace(),
+            id: product.id().to_string(),
+            resolved_at: now(),
+        };
+        let flush = {
+            let mut records = self.lock();
+            let mut pending = self.lock_pending();
+            pending.records.push(record.clone());
+            let since = *pending.since.get_or_insert_with(Instant::now);
+            records.insert(record.url.clone(), record);
+
+            pending.records.len() >= self.flush_records || since.elapsed() >= self.flush_interval
+        };
+        if flush {
+            let _ = self.flush();
+        }
+    }
+}
+
+/// Keeps the most recent record of a URL.
+fn merge(records: &mut HashMap<String, CacheRecord>, record: CacheRecord) {
+    match records.get(&record.url) {
+        Some(existing) if existing.resolved_at > record.resolved_at => {}
+        _ => {
+            records.insert(record.url.clone(), record);
+        }
+    }
+}
+
+fn to_line(record: &CacheRecord) -> String {
+    serde_json::to_string(record).expect("cache records always serialize")
+}
+
+fn store_error(path: &Path, error: io::Error) -> Error {
+    Error::CacheStore {
+        path: path.display().to_string(),
+        reason: error.to_string(),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>;
+
+    use super::*;
+
+    const URL: &str = "https://m.tb.cn/h.abc";
+
+    fn store_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "link-converter-{}-{name}.jsonl",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn product() -> crate::Result<Product> {
+        Product::new(Marketplace::Taobao, "758911450758")
+    }
+
+    #[test]
+    fn test_disk_cache_persists_across_opens() -> Result<()> {
+        // -- Setup & Fixtures
+        let path = store_path("persist");
+
+        // -- Exec
+        DiskCache::open(&path)?.insert(URL, "mobile_taobao", &product());
+        let reopened = DiskCache::open(&path)?;
+
+        // -- Check
+        let cached = reopened.get(URL).ok_or("should be cached")??;
+        assert_eq!(cached.id(), "758911450758");
+
+        let mut exported = Vec::new();
+        reopened.export(&mut exported)?;
+        let record: CacheRecord = serde_json::from_slice(&exported)?;
+        assert_eq!(record.converter, "mobile_taobao");
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_cache_buffers_inserts_until_flush() -> Result<()> {
+        // -- Setup & Fixtures
+        let path = store_path("flush");
+        let cache = DiskCache::open(&path)?;
+
+        // -- Exec
+        cache.insert(URL, "mobile_taobao", &product());
+
+        // -- Check
+        assert!(!path.exists(), "insert should not write the store");
+        cache.flush()?;
+        assert_eq!(DiskCache::open(&path)?.len(), 1);
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_cache_flushes_on_threshold() -> Result<()> {
+        // -- Setup & Fixtures
+        let path = store_path("threshold");
+        let cache = DiskCache::open(&path)?.flush_after(2, Duration::from_secs(60));
+
+        // -- Exec
+        cache.insert(URL, "mobile_taobao", &product());
+        let after_first = path.exists();
+        cache.insert("https://m.tb.cn/h.def", "mobile_taobao", &product());
+
+        // -- Check
+        assert!(!after_first, "one record is below the threshold");
+        assert_eq!(DiskCache::open(&path)?.len(), 2);
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_cache_open_skips_invalid_records() -> Result<()> {
+        // -- Setup & Fixtures
+        let path = store_path("skip");
+        fs::write(
+            &path,
+            concat!(
+                r#"{"url":"https://m.tb.cn/h.bad","converter":"mobile_taobao","marketplace":"taobao","id":"1&platform=WEIDIAN","resolved_at":18446744073709551615}"#,
+                "\n",
+                r#"{"url":"https://m.tb.cn/h.good","converter":"mobile_taobao","marketplace":"taobao","id":"1","resolved_at":18446744073709551615}"#,
+                "\n",
+            ),
+        )?;
+
+        // -- Exec
+        let cache = DiskCache::open(&path)?;
+
+        // -- Check
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get("https://m.tb.cn/h.bad").is_none());
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_cache_import_and_sweep() -> Result<()> {
+        // -- Setup & Fixtures
+        let path = store_path("sweep");
+        let cache = DiskCache::open(&path)?;
+        let records = r#"
+            {"url":"https://m.tb.cn/h.old","converter":"mobile_taobao","marketplace":"taobao","id":"1","resolved_at":0}
+            {"url":"https://k.youshop10.com/new","converter":"you_shop_10","marketplace":"weidian","id":"2","resolved_at":18446744073709551615}
+        "#;
+
+        // -- Exec
+        let imported = cache.import(records.as_bytes())?;
+        let removed = cache.sweep()?;
+
+        // -- Check
+        assert_eq!(imported, 2);
+        assert_eq!(removed, 1);
+        assert!(cache.get("https://m.tb.cn/h.old").is_none());
+        assert_eq!(DiskCache::open(&path)?.len(), 1);
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_import_invalid_record() -> Result<()> {
+        // -- Setup & Fixtures
+        let cache = DiskCache::open(store_path("invalid"))?;
+
+        // -- Exec
+        let actual = cache.import("{\"url\": 1}\n".as_bytes());
+
+        // -- Check
+        assert!(
+            matches!(actual, Err(crate::Error::CacheStore { reason, .. }) if reason.contains("line 1"))
+        );
+        assert!(cache.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_import_invalid_product_id() -> Result<()> {
+        // -- Setup & Fixtures
+        let cache = DiskCache::open(store_path("invalid-id"))?;
+        let records = r#"{"url":"https://m.tb.cn/h.abc","converter":"mobile_taobao","marketplace":"taobao","id":"a b","resolved_at":0}"#;
+
+        // -- Exec
+        let actual = cache.import(records.as_bytes());
+
+        // -- Check
+        assert!(
+            matches!(actual, Err(crate::Error::CacheStore { reason, .. }) if reason.contains("line 1"))
+        );
+        assert!(cache.is_empty());
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
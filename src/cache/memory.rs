@@ -0,0 +1,146 @@
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+
+use super::{ConversionCache, DEFAULT_NEGATIVE_TTL, DEFAULT_TTL};
+use crate::{Product, Result};
+
+/// In-memory [`ConversionCache`] evicting the least recently used entries once full.
+///
+/// Successes expire after [`DEFAULT_TTL`] and failures after the shorter
+/// [`DEFAULT_NEGATIVE_TTL`], unless configured otherwise.
+#[derive(Debug)]
+pub struct MemoryCache {
+    entries: Mutex<LruCache<String, CacheEntry>>,
+    ttl: Duration,
+    negative_ttl: Duration,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    outcome: Result<Product>,
+    expires_at: Instant,
+}
+
+impl MemoryCache {
+    /// Creates a cache holding at most `capacity` URLs.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).expect("cache capacity must be at least 1");
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl: DEFAULT_TTL,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+        }
+    }
+
+    /// Sets how long successful conversions stay cached.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets how long failed conversions stay cached, [`Duration::ZERO`] disables negative caching.
+    pub fn negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Number of cached URLs, expired ones included until they are evicted.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<String, CacheEntry>> {
+        // The entries stay consistent even if a holder panicked.
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ConversionCache for MemoryCache {
+    fn get(&self, url: &str) -> Option<Result<Product>> {
+        let mut entries = self.lock();
+        let entry = entries.get(url)?;
+        if entry.expires_at <= Instant::now() {
+            entries.pop(url);
+            return None;
+        }
+
+        Some(entry.outcome.clone())
+    }
+
+    fn insert(&self, url: &str, _converter: &str, outcome: &Result<Product>) {
+        let ttl = match outcome {
+            Ok(_) => self.ttl,
+            Err(_) => self.negative_ttl,
+        };
+        if ttl.is_zero() {
+            return;
+        }
+
+        let entry = CacheEntry {
+            outcome: outcome.clone(),
+            expires_at: Instant::now() + ttl,
+        };
+        self.lock().put(url.to_string(), entry);
+    }
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Marketplace};
+
+    const URL: &str = "https://m.tb.cn/h.abc";
+
+    #[test]
+    fn test_memory_cache_evicts_least_recently_used() {
+        // -- Setup & Fixtures
+        let cache = MemoryCache::new(2);
+        let product = Product::new(Marketplace::Taobao, "1");
+
+        // -- Exec
+        cache.insert("https://m.tb.cn/h.1", "mobile_taobao", &product);
+        cache.insert("https://m.tb.cn/h.2", "mobile_taobao", &product);
+        cache.get("https://m.tb.cn/h.1");
+        cache.insert("https://m.tb.cn/h.3", "mobile_taobao", &product);
+
+        // -- Check
+        assert!(cache.get("https://m.tb.cn/h.1").is_some());
+        assert!(cache.get("https://m.tb.cn/h.2").is_none());
+        assert!(cache.get("https://m.tb.cn/h.3").is_some());
+    }
+
+    #[test]
+    fn test_memory_cache_expires_failures_separately() {
+        // -- Setup & Fixtures
+        let cache = MemoryCache::new(8).negative_ttl(Duration::ZERO);
+        let error = Err(Error::PageParse {
+            url: URL.into(),
+            converter: "mobile_taobao".into(),
+        });
+
+        // -- Exec
+        cache.insert(URL, "mobile_taobao", &error);
+
+        // -- Check
+        assert!(cache.get(URL).is_none(), "negative caching is disabled");
+
+        let cache = MemoryCache::new(8).ttl(Duration::ZERO);
+        cache.insert(URL, "mobile_taobao", &error);
+        let cached = cache.get(URL);
+        assert!(matches!(cached, Some(Err(Error::PageParse { .. }))));
+    }
+}
+
+// endregion: --- Tests
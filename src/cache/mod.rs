@@ -0,0 +1,53 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use url::Url;
+
+use crate::{Product, Result};
+
+mod disk;
+mod memory;
+
+pub use disk::{CacheRecord, DiskCache, DEFAULT_DISK_TTL};
+pub use memory::MemoryCache;
+
+/// Default time a successful conversion stays cached.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Default time a failed conversion stays cached.
+pub const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(60);
+
+/// Cache of network conversions, consulted by the [`crate::Converter`] before it calls a
+/// network converter.
+///
+/// Keys are the converted URLs without their fragment, values the conversion outcomes,
+/// failures included.
+pub trait ConversionCache: Send + Sync + Debug {
+    /// Returns the cached outcome for the URL, if any and not expired.
+    fn get(&self, url: &str) -> Option<Result<Product>>;
+
+    /// Stores the outcome of converting the URL with the named converter.
+    fn insert(&self, url: &str, converter: &str, outcome: &Result<Product>);
+}
+
+/// A shared cache, so that e.g. a [`DiskCache`] given to a [`crate::ConverterBuilder`] can
+/// still be flushed, swept or exported.
+impl<T: ConversionCache + ?Sized> ConversionCache for Arc<T> {
+    fn get(&self, url: &str) -> Option<Result<Product>> {
+        (**self).get(url)
+    }
+
+    fn insert(&self, url: &str, converter: &str, outcome: &Result<Product>) {
+        (**self).insert(url, converter, outcome);
+    }
+}
+
+/// Normalizes a URL into a cache key, dropping its fragment.
+///
+/// Scheme and host case, default ports and percent-encoding are already normalized by [`Url`].
+pub(crate) fn normalize(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.into()
+}
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::join_all;
+use futures::stream::{FuturesUnordered, Stream};
+use reqwest::Client;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
+use url::Url;
+
+use crate::converters::{self, LinkConverter};
+use crate::rate_limit::RateLimiter;
+use crate::{
+    agent_link, builder, bulk_entries, cache, extract, raw_agent_link, register_rules,
+    rewrite_converted, to_agent_links, unique_links, BulkEntry, ConversionCache, ConversionResult,
+    ConverterBuilder, Error, Product, Result, RetryPolicy, RewriteMode, RewrittenText, RulesFormat,
+};
+
+/// Default number of network conversions running at the same time.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Default time a network converter gets for one conversion attempt.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Main Converter struct holding registered URL conversion strategies.
+#[derive(Debug)]
+pub struct Converter {
+    pub(crate) converters: Vec<Box<dyn LinkConverter>>,
+    pub(crate) network_permits: Semaphore,
+    pub(crate) cache: Option<Box<dyn ConversionCache>>,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) timeout: Duration,
+    pub(crate) converter_timeouts: HashMap<String, Duration>,
+    pub(crate) bulk_deadline: Option<Duration>,
+    pub(crate) rate_limiter: RateLimiter,
+}
+
+impl Converter {
+    /// Creates a new Converter instance with default HTTP client settings.
+    ///
+    /// # Errors
+    /// Returns `Error` if the underlying HTTP client fails to build.
+    pub fn new() -> Result<Self> {
+        ConverterBuilder::new().build()
+    }
+
+    /// Creates a new Converter using a pre-configured HTTP client.
+    ///
+    /// Its suggested to set a redirect Policy.
+    pub fn from_client(client: Client) -> Self {
+        Self {
+            converters: builder::builtin_converters(Arc::new(client)),
+            network_permits: Semaphore::new(DEFAULT_CONCURRENCY),
+            cache: None,
+            retry: RetryPolicy::default(),
+            timeout: DEFAULT_TIMEOUT,
+            converter_timeouts: HashMap::new(),
+            bulk_deadline: None,
+            rate_limiter: RateLimiter::default(),
+        }
+    }
+
+    /// Creates a [`ConverterBuilder`] to customize the registered converters.
+    pub fn builder() -> ConverterBuilder {
+        ConverterBuilder::new()
+    }
+
+    /// Names of the registered converters, in the order they are tried.
+    pub fn names(&self) -> Vec<&str> {
+        self.converters
+            .iter()
+            .map(|converter| converter.name())
+            .collect()
+    }
+
+    /// Loads agent rules from a `.toml` or `.json` file and registers them.
+    ///
+    /// A loaded rule replaces the registered converter of the same name, so existing
+    /// agents can be updated without a rebuild; other rules are added after the built-ins.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidRulesFile` or `Error::InvalidRule` if the file fails validation,
+    /// in which case no rule is registered.
+    pub fn load_rules(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let rules = converters::rule_file::load_rules(path.as_ref())?;
+        register_rules(&mut self.converters, rules);
+
+        Ok(())
+    }
+
+    /// Loads agent rules from the content of a rules file, see [`Converter::load_rules`].
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidRulesFile` or `Error::InvalidRule` if the content fails validation.
+    pub fn load_rules_str(&mut self, content: &str, format: RulesFormat) -> Result<()> {
+        let rules = converters::rule_file::parse_rules(content, format)?;
+        register_rules(&mut self.converters, rules);
+
+        Ok(())
+    }
+
+    /// Converts a single URL using the first applicable converter.
+    ///
+    /// Network converters wait for one of the Converter's concurrency permits,
+    /// see [`ConverterBuilder::concurrency`], and each attempt is bounded by the
+    /// converter's timeout, see [`ConverterBuilder::timeout`].
+    ///
+    /// # Parameters
+    /// - `url`: The URL to be converted
+    ///
+    /// # Errors
+    /// Returns `Error::UnsupportedHost` if no registered converter can handle the URL,
+    /// and `Error::Timeout` if a network converter did not answer in time.
+    pub async fn convert_one(&self, url: Url) -> Result<Product> {
+        let converter = self.find_converter(&url)?;
+
+        self.convert_with(converter, url).await
+    }
+
+    /// Name of the converter [`Converter::convert_one`] uses for the URL, `None` if no
+    /// registered converter supports it.
+    pub fn converter_for(&self, url: &Url) -> Option<&str> {
+        self.find_converter(url)
+            .ok()
+            .map(|converter| converter.name())
+    }
+
+    fn find_converter(&self, url: &Url) -> Result<&dyn LinkConverter> {
+        self.converters
+            .iter()
+            .find(|c| c.can_convert(url))
+            .map(|c| c.as_ref())
+            .ok_or_else(|| Error::UnsupportedHost {
+                url: url.to_string(),
+            })
+    }
+
+    async fn convert_with(&self, converter: &dyn LinkConverter, url: Url) -> Result<Product> {
+        if converter.is_network() {
+            self.convert_network(converter, url).await
+        } else {
+            converter.convert(url).await
+        }
+    }
+
+    /// Converts a URL with a network converter, going through the cache, the
+    /// rate and concurrency limits and the retry policy.
+    async fn convert_network(&self, converter: &dyn LinkConverter, url: Url) -> Result<Product> {
+        let key = cache::normalize(&url);
+        if let Some(cached) = self.cache.as_ref().and_then(|cache| cache.get(&key)) {
+            return cached;
+        }
+
+        let converted = self.convert_with_retries(converter, url).await;
+
+        if let Some(cache) = &self.cache {
+            cache.insert(&key, converter.name(), &converted);
+        }
+        converted
+    }
+
+    async fn convert_with_retries(
+        &self,
+        converter: &dyn LinkConverter,
+        url: Url,
+    ) -> Result<Product> {
+        let mut attempt = 1;
+        loop {
+            if let Some(host) = url.host_str() {
+                self.rate_limiter.acquire(host).await;
+            }
+
+            let converted = {
+                let _permit = self
+                    .network_permits
+                    .acquire()
+                    .await
+                    .expect("network permits are never closed");
+                let timeout = self
+                    .converter_timeouts
+                    .get(converter.name())
+                    .copied()
+                    .unwrap_or(self.timeout);
+                tokio::time::timeout(timeout, converter.convert(url.clone()))
+                    .await
+                    .unwrap_or_else(|_| Err(timed_out(&url, converter)))
+            };
+
+            // Once retried, a failure keeps its attempt count even if it is not retryable.
+            match converted {
+                Err(ref error)
+                    if self.retry.is_retryable(error) && attempt < self.retry.attempts() => {}
+                Err(error) if attempt > 1 => {
+                    return Err(Error::Retried {
+                        attempts: attempt,
+                        last_error: Box::new(error),
+                    })
+                }
+                converted => return converted,
+            }
+
+            tokio::time::sleep(self.retry.delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Parses and converts a URL, returning the name of the converter that handled it.
+    ///
+    /// A conversion still running at the `deadline` fails with `Error::Timeout`.
+    async fn convert_str(
+        &self,
+        url: &str,
+        deadline: Option<Instant>,
+    ) -> (Option<&str>, Result<Product>) {
+        let url = match Url::parse(url) {
+            Ok(url) => url,
+            Err(e) => return (None, Err(e.into())),
+        };
+        let converter = match self.find_converter(&url) {
+            Ok(converter) => converter,
+            Err(e) => return (None, Err(e)),
+        };
+
+        let converting = self.convert_with(converter, url.clone());
+        let converted = match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, converting)
+                .await
+                .unwrap_or_else(|_| Err(timed_out(&url, converter))),
+            None => converting.await,
+        };
+
+        (Some(converter.name()), converted)
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.bulk_deadline.map(|deadline| Instant::now() + deadline)
+    }
+
+    /// Builds the link of the named agent (e.g. `"cnfans"`) for a product.
+    ///
+    /// # Errors
+    /// Returns `Error::UnknownAgent` if no registered converter has that name, or
+    /// `Error::UnsupportedMarketplace` if the agent cannot link to the product's marketplace.
+    pub fn agent_link(&self, product: &Product, agent: &str) -> Result<Url> {
+        agent_link(&self.converters, product, agent)
+    }
+
+    /// Converts a raw marketplace link (Taobao, Weidian, 1688) into the named agent's link.
+    ///
+    /// # Errors
+    /// Returns `Error::UnsupportedHost` if the URL is not a raw marketplace link,
+    /// otherwise see [`Converter::agent_link`].
+    pub fn to_agent(&self, raw_url: Url, agent: &str) -> Result<Url> {
+        raw_agent_link(&self.converters, raw_url, agent)
+    }
+
+    /// Converts any supported link (agent, share or raw marketplace link) into the named agent's link.
+    ///
+    /// # Errors
+    /// See [`Converter::convert_one`] and [`Converter::agent_link`].
+    pub async fn convert_to_agent(&self, url: Url, agent: &str) -> Result<Url> {
+        let product = self.convert_one(url).await?;
+
+        self.agent_link(&product, agent)
+    }
+
+    /// Processes text content to find and convert all links. Returns a [`ConversionResult`].
+    ///
+    /// URLs are converted concurrently, network conversions bounded by the Converter's
+    /// concurrency limit, and the results keep the order the URLs were found in. Identical
+    /// links are converted only once; see [`ConversionResult::group_by_product`] to collapse
+    /// links that resolve to the same product.
+    ///
+    /// With a bulk deadline, see [`ConverterBuilder::bulk_deadline`], links still converting
+    /// when it passes are reported as `Error::Timeout` instead of awaited.
+    ///
+    /// # Note
+    /// Besides `http(s)://` links, scheme-less `www.` and share links (`m.tb.cn/...`) are
+    /// detected. Links end at whitespace or non-ASCII characters, and trailing punctuation
+    /// or unbalanced closing brackets are not considered part of a link.
+    pub async fn convert_bulk(&self, text: &str) -> Result<ConversionResult> {
+        let links = extract::find_links(text);
+
+        // Identical links are converted once and share the outcome.
+        let (unique, positions) = unique_links(&links);
+        let deadline = self.deadline();
+        let conversions = join_all(
+            unique
+                .into_iter()
+                .map(|url| self.convert_str(url, deadline)),
+        )
+        .await;
+        let entries = bulk_entries(links, &positions, &conversions);
+
+        Ok(ConversionResult { entries })
+    }
+
+    /// Processes text content like [`Converter::convert_bulk`], converting every URL into the named agent's link.
+    pub async fn convert_bulk_to_agent(
+        &self,
+        text: &str,
+        agent: &str,
+    ) -> Result<ConversionResult<Url>> {
+        let converted = self.convert_bulk(text).await?;
+
+        Ok(to_agent_links(&self.converters, converted, agent))
+    }
+
+    /// Processes text content like [`Converter::convert_bulk`], yielding each entry as soon
+    /// as it is ready instead of in input order.
+    pub fn convert_stream<'a>(&'a self, text: &'a str) -> impl Stream<Item = BulkEntry> + 'a {
+        let deadline = self.deadline();
+        extract::find_links(text)
+            .into_iter()
+            .map(move |link| self.convert_link(link, deadline))
+            .collect::<FuturesUnordered<_>>()
+    }
+
+    /// Rewrites the text with every convertible link replaced by, or annotated with, its
+    /// converted link. Links that fail to convert are left untouched.
+    pub async fn rewrite_text(&self, text: &str, mode: RewriteMode) -> RewrittenText {
+        rewrite_converted(text, mode, self.convert_bulk(text).await)
+    }
+
+    async fn convert_link(
+        &self,
+        link: extract::FoundLink<'_>,
+        deadline: Option<Instant>,
+    ) -> BulkEntry {
+        let (converter, outcome) = self.convert_str(&link.url, deadline).await;
+
+        BulkEntry {
+            input: link.text.to_string(),
+            span: link.span,
+            converter: converter.map(str::to_string),
+            outcome,
+        }
+    }
+}
+
+fn timed_out(url: &Url, converter: &dyn LinkConverter) -> Error {
+    Error::Timeout {
+        url: url.to_string(),
+        converter: converter.name().to_string(),
+    }
+}
@@ -1,11 +1,11 @@
 use crate::error::Result;
 
-use super::{destination, LinkConverter};
-use async_trait::async_trait;
+use super::SyncLinkConverter;
+use crate::product::{Marketplace, Product};
 use lazy_regex::regex_captures;
 use url::Url;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct CSSBuy;
 
 impl CSSBuy {
@@ -14,31 +14,54 @@ impl CSSBuy {
     }
 }
 
-#[async_trait]
-impl LinkConverter for CSSBuy {
+impl SyncLinkConverter for CSSBuy {
+    fn name(&self) -> &str {
+        "cssbuy"
+    }
+
     fn can_convert(&self, url: &Url) -> bool {
         url.host_str() == Some("www.cssbuy.com") && url.path().starts_with("/item-")
     }
 
-    async fn convert(&self, url: Url) -> Result<String> {
+    fn convert(&self, url: Url) -> Result<Product> {
         let path = url.path();
 
         // Pattern 1: item-{id}.html -> item.taobao.com/item.htm?id={id}
         if let Some((_, id)) = regex_captures!(r"^/item-(\d+)\.html$", path) {
-            return Ok(destination::taobao(id));
+            return Product::new(Marketplace::Taobao, id);
         }
 
         // Pattern 2: item-micro-{id}.html -> weidian.com/item.html?itemID={id}
         if let Some((_, id)) = regex_captures!(r"^/item-micro-(\d+)\.html$", path) {
-            return Ok(destination::weidian(id));
+            return Product::new(Marketplace::Weidian, id);
         }
 
         // Pattern 3: item-1688-{id}.html -> detail.1688.com/offer/{id}.html
         if let Some((_, id)) = regex_captures!(r"^/item-1688-(\d+)\.html$", path) {
-            return Ok(destination::ali_1688(id));
+            return Product::new(Marketplace::Ali1688, id);
         }
 
-        Err(crate::Error::NonConvertableUrl { given_url: url })
+        Err(crate::Error::MissingParameter {
+            url: url.to_string(),
+            converter: self.name().to_string(),
+            parameter: "item id".into(),
+        })
+    }
+
+    fn agent_link(&self, product: &Product) -> Option<Url> {
+        let prefix = match product.marketplace() {
+            Marketplace::Taobao => "item",
+            Marketplace::Weidian => "item-micro",
+            Marketplace::Ali1688 => "item-1688",
+            _ => return None,
+        };
+
+        Url::parse(&format!(
+            "https://www.cssbuy.com/{}-{}.html",
+            prefix,
+            product.id()
+        ))
+        .ok()
     }
 }
 
@@ -68,8 +91,8 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_url_conversion_taobao() -> Result<()> {
+    #[test]
+    fn test_url_conversion_taobao() -> Result<()> {
         // -- Patterns
         let test_cases = [
             (
@@ -90,8 +113,30 @@ mod tests {
             let url = Url::parse(input)?;
             let converter = CSSBuy::new();
 
-            let actual = converter.convert(url).await?;
-            assert_eq!(actual, expected);
+            let actual = converter.convert(url)?;
+            assert_eq!(actual.to_string(), expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_agent_link_round_trip() -> Result<()> {
+        // -- Fixtures
+        let agent_links = [
+            "https://www.cssbuy.com/item-758911450758.html",
+            "https://www.cssbuy.com/item-micro-7322752149.html",
+            "https://www.cssbuy.com/item-1688-681296637536.html",
+        ];
+
+        for agent_link in agent_links {
+            let converter = CSSBuy::new();
+            let product = converter.convert(Url::parse(agent_link)?)?;
+
+            let actual = converter
+                .agent_link(&product)
+                .ok_or("agent link not built")?;
+            assert_eq!(actual.as_str(), agent_link);
         }
 
         Ok(())
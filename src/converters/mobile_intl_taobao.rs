@@ -1,28 +1,30 @@
 use crate::error::Error;
 
-use super::{destination, LinkConverter};
-use async_trait::async_trait;
+use super::SyncLinkConverter;
+use crate::product::{Marketplace, Product};
 use lazy_regex::regex_captures;
 use url::Url;
 
 #[derive(Debug)]
 pub struct MobileIntlTaobao;
 
-#[async_trait]
-impl LinkConverter for MobileIntlTaobao {
+impl SyncLinkConverter for MobileIntlTaobao {
+    fn name(&self) -> &str {
+        "mobile_intl_taobao"
+    }
+
     fn can_convert(&self, url: &Url) -> bool {
         url.host_str() == Some("m.intl.taobao.com")
     }
 
-    async fn convert(&self, url: Url) -> crate::error::Result<String> {
-        let Some((_, item_id)) = regex_captures!(r"(?:id=(\d+))", &url.as_str()) else {
-            return Err(Error::FailedToRedirectUrl { url });
-        };
-
-        if !item_id.is_empty() {
-            Ok(destination::taobao(item_id))
-        } else {
-            Err(Error::FailedToRedirectUrl { url })
+    fn convert(&self, url: Url) -> crate::error::Result<Product> {
+        match regex_captures!(r"(?:id=(\d+))", &url.as_str()) {
+            Some((_, item_id)) if !item_id.is_empty() => Product::new(Marketplace::Taobao, item_id),
+            _ => Err(Error::MissingParameter {
+                url: url.to_string(),
+                converter: self.name().to_string(),
+                parameter: "id".into(),
+            }),
         }
     }
 }
@@ -52,18 +54,18 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_url_conversion() -> Result<()> {
+    #[test]
+    fn test_url_conversion() -> Result<()> {
         // -- Setup & Fixtures
         let url = Url::parse("https://m.intl.taobao.com/detail/detail.html?id=635308355125")?;
 
         // -- Exec
-        let actual_converted_url = MobileIntlTaobao.convert(url).await?;
+        let actual_converted_url = MobileIntlTaobao.convert(url)?;
 
         // -- Check
         let expected_converted_url = "https://item.taobao.com/item.htm?id=635308355125";
 
-        assert_eq!(actual_converted_url, expected_converted_url);
+        assert_eq!(actual_converted_url.to_string(), expected_converted_url);
 
         Ok(())
     }
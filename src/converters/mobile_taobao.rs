@@ -1,41 +1,94 @@
-use crate::error::Error;
+use std::sync::Arc;
 
-use super::LinkConverter;
+use crate::error::{Error, Result};
+
+use super::{LinkConverter, SyncLinkConverter};
+use crate::fetch::{self, BlockingFetcher, Fetcher};
+use crate::product::{Marketplace, Product};
 use async_trait::async_trait;
 use lazy_regex::regex_captures;
-use reqwest::Client;
 use url::Url;
 
+const NAME: &str = "mobile_taobao";
+
+/// Resolves `m.tb.cn` share links by fetching their page.
+///
+/// Converts asynchronously with a [`Fetcher`], e.g. a [`reqwest::Client`], and on the
+/// calling thread with a [`BlockingFetcher`], e.g. a [`reqwest::blocking::Client`].
 #[derive(Debug)]
-pub struct MobileTaobao(Client);
+pub struct MobileTaobao<F: ?Sized = dyn Fetcher>(Arc<F>);
 
 impl MobileTaobao {
-    pub fn new(client: Client) -> Self {
-        MobileTaobao(client)
+    pub fn new(fetcher: Arc<dyn Fetcher>) -> Self {
+        MobileTaobao(fetcher)
+    }
+}
+
+impl MobileTaobao<dyn BlockingFetcher> {
+    pub fn blocking(fetcher: Arc<dyn BlockingFetcher>) -> Self {
+        MobileTaobao(fetcher)
+    }
+}
+
+/// Finds the product in the fetched share page.
+fn parse_page(url: &Url, page: &str) -> Result<Product> {
+    let page_parse = || Error::PageParse {
+        url: url.to_string(),
+        converter: NAME.to_string(),
+    };
+
+    let Some((_, item_id, shop_id)) = regex_captures!(r"(?:itemId=(\d+))|(?:shop(\d+))", page)
+    else {
+        return Err(page_parse());
+    };
+
+    if !item_id.is_empty() {
+        Product::new(Marketplace::Goofish, item_id)
+    } else if !shop_id.is_empty() {
+        Product::new(Marketplace::TaobaoShop, shop_id)
+    } else {
+        Err(page_parse())
     }
 }
 
 #[async_trait]
 impl LinkConverter for MobileTaobao {
+    fn name(&self) -> &str {
+        NAME
+    }
+
     fn can_convert(&self, url: &Url) -> bool {
         url.host_str() == Some("m.tb.cn")
     }
 
-    async fn convert(&self, url: Url) -> crate::error::Result<String> {
-        let resp = self.0.get(url.as_ref()).send().await?.text().await?;
+    async fn convert(&self, url: Url) -> Result<Product> {
+        let page = fetch::get(self.0.as_ref(), &url, NAME).await?;
 
-        let Some((_, item_id, shop_id)) = regex_captures!(r"(?:itemId=(\d+))|(?:shop(\d+))", &resp)
-        else {
-            return Err(Error::FailedToRedirectUrl { url });
-        };
+        parse_page(&url, &page.text())
+    }
 
-        if !item_id.is_empty() {
-            Ok(format!("https://www.goofish.com/item?id={}", item_id))
-        } else if !shop_id.is_empty() {
-            Ok(format!("https://shop{}.world.taobao.com/", shop_id))
-        } else {
-            Err(Error::FailedToRedirectUrl { url })
-        }
+    fn is_network(&self) -> bool {
+        true
+    }
+}
+
+impl SyncLinkConverter for MobileTaobao<dyn BlockingFetcher> {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn can_convert(&self, url: &Url) -> bool {
+        url.host_str() == Some("m.tb.cn")
+    }
+
+    fn convert(&self, url: Url) -> Result<Product> {
+        let page = fetch::get_blocking(self.0.as_ref(), &url, NAME)?;
+
+        parse_page(&url, &page.text())
+    }
+
+    fn is_network(&self) -> bool {
+        true
     }
 }
 
@@ -46,15 +99,32 @@ mod tests {
     type Error = Box<dyn std::error::Error>;
     type Result<T> = core::result::Result<T, Error>; // For tests.
 
+    use reqwest::Client;
     use url::Url;
 
     use super::*;
+    use crate::fetch::{FetchError, FetchRequest, FetchResponse};
+
+    /// Answers every request with a recorded share page.
+    #[derive(Debug)]
+    struct RecordedPage(&'static str);
+
+    impl BlockingFetcher for RecordedPage {
+        fn fetch(&self, request: FetchRequest) -> core::result::Result<FetchResponse, FetchError> {
+            Ok(FetchResponse {
+                status: 200,
+                url: request.url,
+                headers: Vec::new(),
+                body: self.0.as_bytes().to_vec(),
+            })
+        }
+    }
 
     #[test]
     fn test_detects_convertable_url() -> Result<()> {
         // -- Setup & Fixtures
         let url = Url::parse("https://m.tb.cn/h.TjKAehX?tk=Jrdnecne92w")?;
-        let converter = MobileTaobao::new(Client::new());
+        let converter = MobileTaobao::new(Arc::new(Client::new()));
 
         // -- Exec
         let actual_value = converter.can_convert(&url);
@@ -69,7 +139,7 @@ mod tests {
     async fn test_url_conversion_taobao() -> Result<()> {
         // -- Setup & Fixtures
         let url = Url::parse("https://m.tb.cn/h.TTHL3ZZKsh88JtB")?;
-        let converter = MobileTaobao::new(Client::new());
+        let converter = MobileTaobao::new(Arc::new(Client::new()));
 
         // -- Exec
         let actual_converted_url = converter.convert(url).await?;
@@ -77,7 +147,24 @@ mod tests {
         // -- Check
         let expected_converted_url = "https://shop247709762.world.taobao.com/";
 
-        assert_eq!(actual_converted_url, expected_converted_url);
+        assert_eq!(actual_converted_url.to_string(), expected_converted_url);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_url_conversion_with_recorded_page() -> Result<()> {
+        // -- Setup & Fixtures
+        let url = Url::parse("https://m.tb.cn/h.TjKAehX?tk=Jrdnecne92w")?;
+        let page = r#"<script>var url = "https://market.m.taobao.com/app/idleFish-F2e/widle-taobao-rax/page-detail?itemId=745863275436";</script>"#;
+        let converter = MobileTaobao::blocking(Arc::new(RecordedPage(page)));
+
+        // -- Exec
+        let actual = SyncLinkConverter::convert(&converter, url)?;
+
+        // -- Check
+        assert_eq!(actual.marketplace(), Marketplace::Goofish);
+        assert_eq!(actual.id(), "745863275436");
 
         Ok(())
     }
@@ -1,30 +1,142 @@
 use std::fmt::Debug;
 
-use crate::Result;
-use async_trait::async_trait;
+use crate::{Product, Result};
 use url::Url;
 
-pub mod acbuy;
-pub mod cnfans;
+#[cfg(feature = "network")]
+pub use async_trait::async_trait;
+
 pub mod cssbuy;
-pub mod joyabuy;
-pub mod lovegobuy;
 pub mod mobile_intl_taobao;
+#[cfg(feature = "network")]
 pub mod mobile_taobao;
-pub mod mulebuy;
-pub mod oopbuy;
-pub mod ootdbuy;
-pub mod orientdig;
+pub mod raw;
+pub mod rule;
+pub mod rule_file;
+#[cfg(feature = "network")]
 pub mod you_shop_10;
 
+#[cfg(feature = "network")]
 #[async_trait]
-/// A trait that defines how to convert a link into its raw form
+/// A trait that defines how to convert a link into its raw form.
+///
+/// Implementations use the re-exported [`async_trait`](macro@async_trait) attribute and can be
+/// registered through [`crate::ConverterBuilder::register`]. Converters that need no
+/// async I/O should implement [`SyncLinkConverter`] instead, which also makes them
+/// available to the [`crate::BlockingConverter`].
 pub trait LinkConverter: Send + Sync + Debug {
+    /// Unique name of the converter, used to address it as a conversion target.
+    fn name(&self) -> &str;
+
     /// Checks if this converter can handle the given URL.
     fn can_convert(&self, url: &Url) -> bool;
 
     /// Converts the URL into its raw form;
-    async fn convert(&self, url: Url) -> Result<String>;
+    async fn convert(&self, url: Url) -> Result<Product>;
+
+    /// Whether the conversion makes network requests, bounding it by the concurrency limit.
+    fn is_network(&self) -> bool {
+        false
+    }
+
+    /// Builds this agent's link for the given product.
+    ///
+    /// Returns `None` if the converter is not an agent or does not support the product's marketplace.
+    fn agent_link(&self, _product: &Product) -> Option<Url> {
+        None
+    }
+}
+
+/// A converter converting links on the calling thread, without an async runtime.
+///
+/// Offline converters implement this trait and get [`LinkConverter`] for free; network
+/// converters implementing it block while their requests run, so the async
+/// [`crate::Converter`] runs them on tokio's blocking threads. They can be registered
+/// through [`crate::ConverterBuilder::register_sync`], and are the only converters
+/// available without the `network` feature.
+pub trait SyncLinkConverter: Send + Sync + Debug {
+    /// See [`LinkConverter::name`].
+    fn name(&self) -> &str;
+
+    /// See [`LinkConverter::can_convert`].
+    fn can_convert(&self, url: &Url) -> bool;
+
+    /// See [`LinkConverter::convert`].
+    fn convert(&self, url: Url) -> Result<Product>;
+
+    /// See [`LinkConverter::is_network`].
+    fn is_network(&self) -> bool {
+        false
+    }
+
+    /// See [`LinkConverter::agent_link`].
+    fn agent_link(&self, _product: &Product) -> Option<Url> {
+        None
+    }
+}
+
+#[cfg(feature = "network")]
+#[async_trait]
+impl<T: SyncLinkConverter> LinkConverter for T {
+    fn name(&self) -> &str {
+        SyncLinkConverter::name(self)
+    }
+
+    fn can_convert(&self, url: &Url) -> bool {
+        SyncLinkConverter::can_convert(self, url)
+    }
+
+    async fn convert(&self, url: Url) -> Result<Product> {
+        SyncLinkConverter::convert(self, url)
+    }
+
+    fn is_network(&self) -> bool {
+        SyncLinkConverter::is_network(self)
+    }
+
+    fn agent_link(&self, product: &Product) -> Option<Url> {
+        SyncLinkConverter::agent_link(self, product)
+    }
+}
+
+/// A boxed converter of either trait, as registered in a [`crate::Converter`] or a
+/// [`crate::BlockingConverter`], for the helpers both share.
+pub(crate) trait Registered {
+    fn name(&self) -> &str;
+
+    fn agent_link(&self, product: &Product) -> Option<Url>;
+
+    /// Boxes a converter loaded from a rules file.
+    fn from_rule(converter: rule::RuleConverter) -> Box<Self>;
+}
+
+impl Registered for dyn SyncLinkConverter {
+    fn name(&self) -> &str {
+        SyncLinkConverter::name(self)
+    }
+
+    fn agent_link(&self, product: &Product) -> Option<Url> {
+        SyncLinkConverter::agent_link(self, product)
+    }
+
+    fn from_rule(converter: rule::RuleConverter) -> Box<Self> {
+        Box::new(converter)
+    }
+}
+
+#[cfg(feature = "network")]
+impl Registered for dyn LinkConverter {
+    fn name(&self) -> &str {
+        LinkConverter::name(self)
+    }
+
+    fn agent_link(&self, product: &Product) -> Option<Url> {
+        LinkConverter::agent_link(self, product)
+    }
+
+    fn from_rule(converter: rule::RuleConverter) -> Box<Self> {
+        Box::new(converter)
+    }
 }
 
 pub mod destination {
@@ -39,4 +151,12 @@ pub mod destination {
     pub fn ali_1688(id: &str) -> String {
         format!("https://detail.1688.com/offer/{}.html", id)
     }
+
+    pub fn goofish(id: &str) -> String {
+        format!("https://www.goofish.com/item?id={}", id)
+    }
+
+    pub fn taobao_shop(id: &str) -> String {
+        format!("https://shop{}.world.taobao.com/", id)
+    }
 }
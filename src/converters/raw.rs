@@ -0,0 +1,205 @@
+use crate::error::{Error, Result};
+
+use super::SyncLinkConverter;
+use crate::product::{is_product_id, Marketplace, Product};
+use lazy_regex::{regex_captures, regex_is_match};
+use url::Url;
+
+/// Normalizes raw Taobao, Tmall, Weidian and 1688 links, including their mobile
+/// variants, to the canonical links of [`super::destination`].
+///
+/// Tracking parameters such as `spm`, `scm` or `wfr` are dropped. Tmall items share
+/// their ids with Taobao and are normalized to Taobao links.
+#[derive(Debug, Default)]
+pub struct RawMarketplace;
+
+impl RawMarketplace {
+    pub fn new() -> Self {
+        RawMarketplace
+    }
+}
+
+impl SyncLinkConverter for RawMarketplace {
+    fn name(&self) -> &str {
+        "marketplace"
+    }
+
+    /// Only item links are claimed, so that e.g. Taobao shop links are left to other converters.
+    fn can_convert(&self, url: &Url) -> bool {
+        parse(url).is_some()
+    }
+
+    fn convert(&self, url: Url) -> Result<Product> {
+        parse(&url).ok_or_else(|| Error::MissingParameter {
+            url: url.to_string(),
+            converter: self.name().to_string(),
+            parameter: "item id".into(),
+        })
+    }
+}
+
+/// Parses a raw marketplace link into its product.
+pub fn parse(url: &Url) -> Option<Product> {
+    let query_id = |names: &[&str]| {
+        url.query_pairs()
+            .find(|(key, value)| names.contains(&key.as_ref()) && is_product_id(value))
+            .map(|(_, value)| value.to_string())
+    };
+    let host = url.host_str()?;
+    let path = url.path();
+
+    // Taobao & Tmall: item.taobao.com, h5.m.taobao.com, detail.tmall.com, detail.m.tmall.com...
+    if regex_is_match!(r"(?:^|\.)(?:taobao\.com|tmall\.com|tmall\.hk)$", host) {
+        if let Some((_, id)) = regex_captures!(r"^/(?:item/|i)(\d+)\.htm", path) {
+            return Product::new(Marketplace::Taobao, id).ok();
+        }
+        return query_id(&["id", "itemId", "item_id"])
+            .and_then(|id| Product::new(Marketplace::Taobao, id).ok());
+    }
+
+    // Weidian: weidian.com/item.html, shop123.v.weidian.com/item.html, h5.weidian.com/...
+    if regex_is_match!(r"(?:^|\.)weidian\.com$", host) {
+        return query_id(&["itemID", "itemId", "id"])
+            .and_then(|id| Product::new(Marketplace::Weidian, id).ok());
+    }
+
+    // 1688: detail.1688.com/offer/{id}.html, m.1688.com/offer/{id}.html, detail.m.1688.com/...?offerId=
+    if regex_is_match!(r"(?:^|\.)1688\.com$", host) {
+        if let Some((_, id)) = regex_captures!(r"^/offer/(\d+)\.html", path) {
+            return Product::new(Marketplace::Ali1688, id).ok();
+        }
+        return query_id(&["offerId", "offerid"])
+            .and_then(|id| Product::new(Marketplace::Ali1688, id).ok());
+    }
+
+    None
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_canonical_links() -> Result<()> {
+        // -- Patterns
+        let test_cases = [
+            (
+                "https://item.taobao.com/item.htm?id=758911450758",
+                Marketplace::Taobao,
+                "758911450758",
+            ),
+            (
+                "https://weidian.com/item.html?itemID=7322752149",
+                Marketplace::Weidian,
+                "7322752149",
+            ),
+            (
+                "https://detail.1688.com/offer/681296637536.html",
+                Marketplace::Ali1688,
+                "681296637536",
+            ),
+        ];
+
+        for (input, marketplace, id) in test_cases {
+            let product = parse(&Url::parse(input)?).ok_or("link not parsed")?;
+
+            assert_eq!(product.marketplace(), marketplace);
+            assert_eq!(product.id(), id);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_url_conversion() -> Result<()> {
+        // -- Patterns
+        let test_cases = [
+            (
+                "https://item.taobao.com/item.htm?spm=a1z10.3-c.w4002.1.6d1f&id=586064449302&scm=1007.40986",
+                "https://item.taobao.com/item.htm?id=586064449302",
+            ),
+            (
+                "https://detail.tmall.com/item.htm?abbucket=2&id=673424156720&rn=ac0&spm=a1z10.3",
+                "https://item.taobao.com/item.htm?id=673424156720",
+            ),
+            (
+                "https://h5.m.taobao.com/awp/core/detail.htm?ft=t&id=586064449302&share_crt_v=1",
+                "https://item.taobao.com/item.htm?id=586064449302",
+            ),
+            (
+                "https://detail.m.tmall.com/item.htm?id=673424156720",
+                "https://item.taobao.com/item.htm?id=673424156720",
+            ),
+            (
+                "https://world.taobao.com/item/586064449302.htm?spm=a21wu",
+                "https://item.taobao.com/item.htm?id=586064449302",
+            ),
+            (
+                "https://weidian.com/item.html?itemID=7322752149&wfr=wx&share_relation=e0fd773",
+                "https://weidian.com/item.html?itemID=7322752149",
+            ),
+            (
+                "https://shop1234.v.weidian.com/item.html?itemID=7322752149",
+                "https://weidian.com/item.html?itemID=7322752149",
+            ),
+            (
+                "https://m.1688.com/offer/681296637536.html?spm=a26g8",
+                "https://detail.1688.com/offer/681296637536.html",
+            ),
+            (
+                "https://detail.m.1688.com/page/index.html?offerId=681296637536",
+                "https://detail.1688.com/offer/681296637536.html",
+            ),
+        ];
+
+        for (input, expected) in test_cases {
+            let url = Url::parse(input)?;
+            let converter = RawMarketplace::new();
+            assert!(converter.can_convert(&url), "should detect {input}");
+
+            let actual = converter.convert(url)?;
+            assert_eq!(actual.to_string(), expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rejects_agent_link() -> Result<()> {
+        // -- Setup & Fixtures
+        let url = Url::parse("https://cnfans.com/product?id=758911450758&platform=TAOBAO")?;
+
+        // -- Check
+        assert!(parse(&url).is_none());
+        assert!(!RawMarketplace::new().can_convert(&url));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_does_not_claim_shop_links() -> Result<()> {
+        for input in [
+            "https://shop247709762.world.taobao.com/",
+            "https://item.taobao.com/item.htm?id=",
+            "https://weidian.com/?userid=1234",
+        ] {
+            // -- Setup & Fixtures
+            let url = Url::parse(input)?;
+
+            // -- Check
+            assert!(
+                !RawMarketplace::new().can_convert(&url),
+                "should not claim {input}"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
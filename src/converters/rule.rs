@@ -0,0 +1,577 @@
+use crate::error::{Error, Result};
+
+use super::SyncLinkConverter;
+use crate::product::{Marketplace, Product};
+use derive_more::Display;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use url::Url;
+
+/// How a rule matches the path of an agent link.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathMatch {
+    Exact(String),
+    Prefix(String),
+}
+
+impl PathMatch {
+    fn matches(&self, path: &str) -> bool {
+        match self {
+            PathMatch::Exact(expected) => path == expected,
+            PathMatch::Prefix(prefix) => path.starts_with(prefix.as_str()),
+        }
+    }
+}
+
+/// Where a rule reads a value from in an agent link.
+#[derive(Debug, Display, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    /// A query parameter, e.g. `id` in `?id=123`.
+    #[display("{_0}")]
+    Query(String),
+    /// A non-empty path segment by zero-based position, e.g. `2` in `/product/1/123`.
+    #[display("path segment {_0}")]
+    Segment(usize),
+}
+
+impl Source {
+    /// The value found in the URL, `None` if it is missing or empty.
+    fn extract(&self, url: &Url) -> Option<String> {
+        match self {
+            Source::Query(key) => url
+                .query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.to_string())
+                .filter(|value| !value.is_empty()),
+            Source::Segment(index) => url
+                .path()
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .nth(*index)
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Describes an agent whose links carry the product id and platform in the URL.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// Unique name of the agent, e.g. `cnfans`.
+    pub name: String,
+    /// Lowercase hosts the agent links are served from.
+    pub hosts: Vec<String>,
+    pub path: PathMatch,
+    /// Where the product id is found.
+    pub id: Source,
+    /// Where the platform value is found.
+    pub platform: Source,
+    /// Platform values of the agent and the marketplace they stand for, one of taobao,
+    /// weidian or ali1688.
+    #[serde(deserialize_with = "deserialize_platforms")]
+    pub platforms: Vec<(String, Marketplace)>,
+    /// Agent link with `{id}` and `{platform}` placeholders, used to build links.
+    pub link_template: String,
+}
+
+impl Rule {
+    /// Checks that the rule is usable, returning the reason if it is not.
+    pub fn validate(&self) -> core::result::Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("`name` must not be empty".into());
+        }
+
+        if self.hosts.is_empty() {
+            return Err("`hosts` must list at least one host".into());
+        }
+        for host in &self.hosts {
+            if url::Host::parse(host).is_err() || host.contains(['/', ':']) {
+                return Err(format!("`hosts` entry `{host}` is not a valid host name"));
+            }
+            if host.chars().any(|c| c.is_ascii_uppercase()) {
+                return Err(format!(
+                    "`hosts` entry `{host}` must be lowercase, as in `{}`",
+                    host.to_ascii_lowercase()
+                ));
+            }
+        }
+
+        let (PathMatch::Exact(path) | PathMatch::Prefix(path)) = &self.path;
+        if !path.starts_with('/') {
+            return Err(format!("`path` `{path}` must start with `/`"));
+        }
+
+        for (field, source) in [("id", &self.id), ("platform", &self.platform)] {
+            if matches!(source, Source::Query(key) if key.is_empty()) {
+                return Err(format!("`{field}` query parameter name must not be empty"));
+            }
+        }
+        if self.id == self.platform {
+            return Err("`id` and `platform` must be read from different places".into());
+        }
+
+        if self.platforms.is_empty() {
+            return Err("`platforms` must map at least one platform value".into());
+        }
+        for (value, marketplace) in &self.platforms {
+            if !matches!(
+                marketplace,
+                Marketplace::Taobao | Marketplace::Weidian | Marketplace::Ali1688
+            ) {
+                return Err(format!(
+                    "`platforms` value `{value}` must map to taobao, weidian or ali1688"
+                ));
+            }
+        }
+
+        for placeholder in ["{id}", "{platform}"] {
+            if !self.link_template.contains(placeholder) {
+                return Err(format!(
+                    "`link_template` is missing the `{placeholder}` placeholder"
+                ));
+            }
+        }
+        let sample = self
+            .link_template
+            .replace("{id}", "1")
+            .replace("{platform}", &self.platforms[0].0);
+        if let Err(e) = Url::parse(&sample) {
+            return Err(format!("`link_template` is not a valid URL: {e}"));
+        }
+
+        Ok(())
+    }
+}
+
+fn deserialize_platforms<'de, D>(
+    deserializer: D,
+) -> core::result::Result<Vec<(String, Marketplace)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let platforms = BTreeMap::<String, Marketplace>::deserialize(deserializer)?;
+
+    Ok(platforms.into_iter().collect())
+}
+
+/// A converter driven by a [`Rule`].
+#[derive(Debug)]
+pub struct RuleConverter(Rule);
+
+impl RuleConverter {
+    pub fn new(rule: Rule) -> Self {
+        RuleConverter(rule)
+    }
+}
+
+impl SyncLinkConverter for RuleConverter {
+    fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    fn can_convert(&self, url: &Url) -> bool {
+        url.host_str()
+            .is_some_and(|host| self.0.hosts.iter().any(|h| h == host))
+            && self.0.path.matches(url.path())
+    }
+
+    fn convert(&self, url: Url) -> Result<Product> {
+        let extract = |source: &Source| {
+            source.extract(&url).ok_or_else(|| Error::MissingParameter {
+                url: url.to_string(),
+                converter: self.0.name.clone(),
+                parameter: source.to_string(),
+            })
+        };
+        let id = extract(&self.0.id)?;
+        let platform = extract(&self.0.platform)?;
+
+        match self
+            .0
+            .platforms
+            .iter()
+            .find(|(value, _)| *value == platform)
+        {
+            Some((_, marketplace)) => Product::new(*marketplace, id),
+            None => Err(Error::UnknownPlatform {
+                url: url.to_string(),
+                converter: self.0.name.clone(),
+                value: platform,
+            }),
+        }
+    }
+
+    fn agent_link(&self, product: &Product) -> Option<Url> {
+        let (platform, _) = self
+            .0
+            .platforms
+            .iter()
+            .find(|(_, marketplace)| *marketplace == product.marketplace())?;
+
+        let link = self
+            .0
+            .link_template
+            .replace("{id}", product.id())
+            .replace("{platform}", platform);
+
+        Url::parse(&link).ok()
+    }
+}
+
+// region:    --- Built-in Rules
+
+/// Rules of the built-in agents.
+pub fn builtin_rules() -> Vec<Rule> {
+    let shop_types = [
+        ("taobao", Marketplace::Taobao),
+        ("weidian", Marketplace::Weidian),
+        ("ali_1688", Marketplace::Ali1688),
+    ];
+
+    vec![
+        Rule {
+            name: "lovegobuy".into(),
+            hosts: vec!["m.lovegobuy.com".into()],
+            path: PathMatch::Exact("/product".into()),
+            id: Source::Query("id".into()),
+            platform: Source::Query("shop_type".into()),
+            platforms: platforms([
+                ("taobao", Marketplace::Taobao),
+                ("weidian", Marketplace::Weidian),
+                ("1688", Marketplace::Ali1688),
+            ]),
+            link_template: "https://m.lovegobuy.com/product?shop_type={platform}&id={id}".into(),
+        },
+        Rule {
+            name: "mulebuy".into(),
+            hosts: vec!["mulebuy.com".into()],
+            path: PathMatch::Prefix("/product/".into()),
+            id: Source::Query("id".into()),
+            platform: Source::Query("shop_type".into()),
+            platforms: platforms(shop_types),
+            link_template: "https://mulebuy.com/product/?shop_type={platform}&id={id}".into(),
+        },
+        Rule {
+            name: "ootdbuy".into(),
+            hosts: vec!["www.ootdbuy.com".into()],
+            path: PathMatch::Prefix("/goods/details".into()),
+            id: Source::Query("id".into()),
+            platform: Source::Query("channel".into()),
+            platforms: platforms([
+                ("TAOBAO", Marketplace::Taobao),
+                ("weidian", Marketplace::Weidian),
+                ("1688", Marketplace::Ali1688),
+            ]),
+            link_template: "https://www.ootdbuy.com/goods/details?id={id}&channel={platform}"
+                .into(),
+        },
+        Rule {
+            name: "cnfans".into(),
+            hosts: vec!["cnfans.com".into()],
+            path: PathMatch::Prefix("/product".into()),
+            id: Source::Query("id".into()),
+            platform: Source::Query("platform".into()),
+            platforms: platforms([
+                ("TAOBAO", Marketplace::Taobao),
+                ("WEIDIAN", Marketplace::Weidian),
+                ("ALI_1688", Marketplace::Ali1688),
+            ]),
+            link_template: "https://cnfans.com/product?id={id}&platform={platform}".into(),
+        },
+        Rule {
+            name: "orientdig".into(),
+            hosts: vec!["orientdig.com".into()],
+            path: PathMatch::Prefix("/product/".into()),
+            id: Source::Query("id".into()),
+            platform: Source::Query("shop_type".into()),
+            platforms: platforms(shop_types),
+            link_template: "https://orientdig.com/product/?shop_type={platform}&id={id}".into(),
+        },
+        Rule {
+            name: "oopbuy".into(),
+            hosts: vec!["oopbuy.com".into()],
+            path: PathMatch::Prefix("/product/".into()),
+            id: Source::Segment(2),
+            platform: Source::Segment(1),
+            platforms: platforms([
+                ("1", Marketplace::Taobao),
+                ("weidian", Marketplace::Weidian),
+                ("0", Marketplace::Ali1688),
+            ]),
+            link_template: "https://oopbuy.com/product/{platform}/{id}".into(),
+        },
+        Rule {
+            name: "joyabuy".into(),
+            hosts: vec!["joyabuy.com".into()],
+            path: PathMatch::Prefix("/product/".into()),
+            id: Source::Query("id".into()),
+            platform: Source::Query("shop_type".into()),
+            platforms: platforms(shop_types),
+            link_template: "https://joyabuy.com/product/?shop_type={platform}&id={id}".into(),
+        },
+        Rule {
+            name: "acbuy".into(),
+            hosts: vec!["www.acbuy.com".into()],
+            path: PathMatch::Prefix("/product".into()),
+            id: Source::Query("id".into()),
+            platform: Source::Query("source".into()),
+            platforms: platforms([
+                ("TB", Marketplace::Taobao),
+                ("WD", Marketplace::Weidian),
+                ("AL", Marketplace::Ali1688),
+            ]),
+            link_template: "https://www.acbuy.com/product?id={id}&source={platform}".into(),
+        },
+    ]
+}
+
+fn platforms<const N: usize>(values: [(&str, Marketplace); N]) -> Vec<(String, Marketplace)> {
+    values
+        .into_iter()
+        .map(|(value, marketplace)| (value.to_string(), marketplace))
+        .collect()
+}
+
+// endregion: --- Built-in Rules
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>;
+
+    use super::*;
+
+    const TAOBAO: &str = "https://item.taobao.com/item.htm?id=758911450758";
+    const WEIDIAN: &str = "https://weidian.com/item.html?itemID=7322752149";
+    const ALI_1688: &str = "https://detail.1688.com/offer/681296637536.html";
+
+    // (agent, agent link, raw link)
+    const TEST_CASES: &[(&str, &str, &str)] = &[
+        (
+            "lovegobuy",
+            "https://m.lovegobuy.com/product?shop_type=taobao&id=758911450758",
+            TAOBAO,
+        ),
+        (
+            "lovegobuy",
+            "https://m.lovegobuy.com/product?shop_type=weidian&id=7322752149",
+            WEIDIAN,
+        ),
+        (
+            "lovegobuy",
+            "https://m.lovegobuy.com/product?shop_type=1688&id=681296637536",
+            ALI_1688,
+        ),
+        (
+            "mulebuy",
+            "https://mulebuy.com/product/?shop_type=taobao&id=758911450758",
+            TAOBAO,
+        ),
+        (
+            "mulebuy",
+            "https://mulebuy.com/product/?shop_type=weidian&id=7322752149",
+            WEIDIAN,
+        ),
+        (
+            "mulebuy",
+            "https://mulebuy.com/product/?shop_type=ali_1688&id=681296637536",
+            ALI_1688,
+        ),
+        (
+            "ootdbuy",
+            "https://www.ootdbuy.com/goods/details?id=758911450758&channel=TAOBAO",
+            TAOBAO,
+        ),
+        (
+            "ootdbuy",
+            "https://www.ootdbuy.com/goods/details?id=7322752149&channel=weidian",
+            WEIDIAN,
+        ),
+        (
+            "ootdbuy",
+            "https://www.ootdbuy.com/goods/details?id=681296637536&channel=1688",
+            ALI_1688,
+        ),
+        (
+            "cnfans",
+            "https://cnfans.com/product?id=758911450758&platform=TAOBAO",
+            TAOBAO,
+        ),
+        (
+            "cnfans",
+            "https://cnfans.com/product?id=7322752149&platform=WEIDIAN",
+            WEIDIAN,
+        ),
+        (
+            "cnfans",
+            "https://cnfans.com/product?id=681296637536&platform=ALI_1688",
+            ALI_1688,
+        ),
+        (
+            "orientdig",
+            "https://orientdig.com/product/?shop_type=taobao&id=758911450758",
+            TAOBAO,
+        ),
+        (
+            "orientdig",
+            "https://orientdig.com/product/?shop_type=weidian&id=7322752149",
+            WEIDIAN,
+        ),
+        (
+            "orientdig",
+            "https://orientdig.com/product/?shop_type=ali_1688&id=681296637536",
+            ALI_1688,
+        ),
+        (
+            "oopbuy",
+            "https://oopbuy.com/product/1/758911450758",
+            TAOBAO,
+        ),
+        (
+            "oopbuy",
+            "https://oopbuy.com/product/weidian/7322752149",
+            WEIDIAN,
+        ),
+        (
+            "oopbuy",
+            "https://oopbuy.com/product/0/681296637536",
+            ALI_1688,
+        ),
+        (
+            "joyabuy",
+            "https://joyabuy.com/product/?shop_type=taobao&id=758911450758",
+            TAOBAO,
+        ),
+        (
+            "joyabuy",
+            "https://joyabuy.com/product/?shop_type=weidian&id=7322752149",
+            WEIDIAN,
+        ),
+        (
+            "joyabuy",
+            "https://joyabuy.com/product/?shop_type=ali_1688&id=681296637536",
+            ALI_1688,
+        ),
+        (
+            "acbuy",
+            "https://www.acbuy.com/product?id=758911450758&source=TB",
+            TAOBAO,
+        ),
+        (
+            "acbuy",
+            "https://www.acbuy.com/product?id=7322752149&source=WD",
+            WEIDIAN,
+        ),
+        (
+            "acbuy",
+            "https://www.acbuy.com/product?id=681296637536&source=AL",
+            ALI_1688,
+        ),
+    ];
+
+    fn builtin(name: &str) -> Result<RuleConverter> {
+        let rule = builtin_rules()
+            .into_iter()
+            .find(|rule| rule.name == name)
+            .ok_or("no such built-in rule")?;
+
+        Ok(RuleConverter::new(rule))
+    }
+
+    #[test]
+    fn test_detects_convertable_url() -> Result<()> {
+        for (agent, agent_link, _) in TEST_CASES {
+            // -- Setup & Fixtures
+            let url = Url::parse(agent_link)?;
+            let converter = builtin(agent)?;
+
+            // -- Exec
+            let actual_value = converter.can_convert(&url);
+
+            // -- Check
+            assert!(actual_value, "{agent} should detect {agent_link}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_url_conversion() -> Result<()> {
+        for (agent, agent_link, expected) in TEST_CASES {
+            let url = Url::parse(agent_link)?;
+            let converter = builtin(agent)?;
+
+            let actual = converter.convert(url)?;
+            assert_eq!(actual.to_string(), *expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_agent_link_round_trip() -> Result<()> {
+        for (agent, agent_link, _) in TEST_CASES {
+            let converter = builtin(agent)?;
+            let product = converter.convert(Url::parse(agent_link)?)?;
+
+            let actual = converter
+                .agent_link(&product)
+                .ok_or("agent link not built")?;
+            assert_eq!(actual.as_str(), *agent_link);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_missing_or_invalid_id() -> Result<()> {
+        // -- Setup & Fixtures
+        let converter = builtin("cnfans")?;
+        let url = |id: &str| {
+            Url::parse(&format!(
+                "https://cnfans.com/product?id={id}&platform=TAOBAO"
+            ))
+        };
+
+        // -- Exec
+        let missing = converter.convert(url("")?);
+
+        // -- Check
+        assert!(
+            matches!(&missing, Err(crate::Error::MissingParameter { parameter, .. }) if parameter == "id"),
+            "an empty id should be missing, got {missing:?}"
+        );
+        for id in ["abc", "1%26platform%3DWEIDIAN"] {
+            let actual = converter.convert(url(id)?);
+            assert!(
+                matches!(actual, Err(crate::Error::InvalidProductId { .. })),
+                "{id:?} should be invalid, got {actual:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_unknown_platform() -> Result<()> {
+        // -- Setup & Fixtures
+        let url = Url::parse("https://cnfans.com/product?id=758911450758&platform=JD")?;
+        let converter = builtin("cnfans")?;
+
+        // -- Exec
+        let actual = converter.convert(url);
+
+        // -- Check
+        assert!(
+            matches!(actual, Err(crate::Error::UnknownPlatform { value, .. }) if value == "JD")
+        );
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
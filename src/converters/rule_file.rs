@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use super::rule::Rule;
+use crate::error::{Error, Result};
+
+/// Format of a rules file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RulesFormat {
+    Toml,
+    Json,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    rules: Vec<Rule>,
+}
+
+/// Loads and validates the rules of a `.toml` or `.json` rules file.
+///
+/// # Errors
+/// Returns `Error::InvalidRulesFile` if the file cannot be read or parsed, and
+/// `Error::InvalidRule` if a rule fails validation.
+pub fn load_rules(path: &Path) -> Result<Vec<Rule>> {
+    let source = path.display().to_string();
+    let invalid_file = |reason: String| Error::InvalidRulesFile {
+        path: source.clone(),
+        reason,
+    };
+
+    let format = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => RulesFormat::Toml,
+        Some("json") => RulesFormat::Json,
+        _ => return Err(invalid_file("expected a `.toml` or `.json` file".into())),
+    };
+    let content = fs::read_to_string(path).map_err(|e| invalid_file(e.to_string()))?;
+
+    parse(&content, format, &source)
+}
+
+/// Parses and validates rules from the content of a rules file.
+///
+/// # Errors
+/// See [`load_rules`].
+pub fn parse_rules(content: &str, format: RulesFormat) -> Result<Vec<Rule>> {
+    parse(content, format, "<inline>")
+}
+
+fn parse(content: &str, format: RulesFormat, source: &str) -> Result<Vec<Rule>> {
+    let parsed = match format {
+        RulesFormat::Toml => toml::from_str::<RulesFile>(content).map_err(|e| e.to_string()),
+        RulesFormat::Json => serde_json::from_str::<RulesFile>(content).map_err(|e| e.to_string()),
+    };
+    let rules = parsed
+        .map_err(|reason| Error::InvalidRulesFile {
+            path: source.to_string(),
+            reason,
+        })?
+        .rules;
+
+    let mut names = HashSet::new();
+    for (index, rule) in rules.iter().enumerate() {
+        let invalid_rule = |reason: String| Error::InvalidRule {
+            index,
+            name: rule.name.clone(),
+            reason,
+        };
+
+        rule.validate().map_err(invalid_rule)?;
+        if !names.insert(rule.name.as_str()) {
+            return Err(invalid_rule(
+                "`name` is already used by another rule".into(),
+            ));
+        }
+    }
+
+    Ok(rules)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>;
+
+    use url::Url;
+
+    use super::*;
+    use crate::converters::rule::{PathMatch, RuleConverter, Source};
+    use crate::converters::SyncLinkConverter;
+
+    const TOML_RULES: &str = r#"
+        [[rules]]
+        name = "newbuy"
+        hosts = ["newbuy.com", "www.newbuy.com"]
+        path = { prefix = "/item/" }
+        id = { segment = 2 }
+        platform = { segment = 1 }
+        platforms = { tb = "taobao", wd = "weidian", al = "ali1688" }
+        link_template = "https://newbuy.com/item/{platform}/{id}"
+    "#;
+
+    #[test]
+    fn test_parse_toml_rules() -> Result<()> {
+        // -- Exec
+        let rules = parse_rules(TOML_RULES, RulesFormat::Toml)?;
+
+        // -- Check
+        let [rule] = rules.as_slice() else {
+            return Err("expected one rule".into());
+        };
+        assert_eq!(rule.path, PathMatch::Prefix("/item/".into()));
+        assert_eq!(rule.id, Source::Segment(2));
+
+        let converter = RuleConverter::new(rule.clone());
+        let product =
+            converter.convert(Url::parse("https://www.newbuy.com/item/wd/7322752149")?)?;
+        assert_eq!(
+            product.to_string(),
+            "https://weidian.com/item.html?itemID=7322752149"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_json_rules() -> Result<()> {
+        // -- Setup & Fixtures
+        let json = r#"{ "rules": [{
+            "name": "newbuy",
+            "hosts": ["newbuy.com"],
+            "path": { "exact": "/product" },
+            "id": { "query": "id" },
+            "platform": { "query": "type" },
+            "platforms": { "TB": "taobao" },
+            "link_template": "https://newbuy.com/product?id={id}&type={platform}"
+        }] }"#;
+
+        // -- Exec
+        let rules = parse_rules(json, RulesFormat::Json)?;
+
+        // -- Check
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].platform, Source::Query("type".into()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_invalid_rule_points_to_rule() -> Result<()> {
+        // -- Setup & Fixtures
+        let content = TOML_RULES.replace("{platform}/{id}", "{id}");
+
+        // -- Exec
+        let actual = parse_rules(&content, RulesFormat::Toml);
+
+        // -- Check
+        let Err(crate::Error::InvalidRule {
+            index,
+            name,
+            reason,
+        }) = actual
+        else {
+            return Err("expected an invalid rule error".into());
+        };
+        assert_eq!(index, 0);
+        assert_eq!(name, "newbuy");
+        assert!(reason.contains("{platform}"), "{reason}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_item_less_marketplace() -> Result<()> {
+        for marketplace in ["goofish", "taobao_shop"] {
+            // -- Setup & Fixtures
+            let content = TOML_RULES.replace("\"ali1688\"", &format!("\"{marketplace}\""));
+
+            // -- Exec
+            let actual = parse_rules(&content, RulesFormat::Toml);
+
+            // -- Check
+            let Err(crate::Error::InvalidRule { reason, .. }) = actual else {
+                return Err(format!("expected {marketplace} to be rejected").into());
+            };
+            assert!(reason.contains("`al`"), "{reason}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_uppercase_host() -> Result<()> {
+        // -- Setup & Fixtures
+        let content = TOML_RULES.replace("\"www.newbuy.com\"", "\"www.NewBuy.com\"");
+
+        // -- Exec
+        let actual = parse_rules(&content, RulesFormat::Toml);
+
+        // -- Check
+        let Err(crate::Error::InvalidRule { reason, .. }) = actual else {
+            return Err("expected an uppercase host to be rejected".into());
+        };
+        assert!(reason.contains("www.newbuy.com"), "{reason}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_unknown_marketplace() -> Result<()> {
+        // -- Setup & Fixtures
+        let content = TOML_RULES.replace("\"ali1688\"", "\"jd\"");
+
+        // -- Exec
+        let actual = parse_rules(&content, RulesFormat::Toml);
+
+        // -- Check
+        let Err(crate::Error::InvalidRulesFile { reason, .. }) = actual else {
+            return Err("expected an invalid rules file error".into());
+        };
+        assert!(reason.contains("jd"), "{reason}");
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
@@ -1,39 +1,90 @@
 use core::fmt;
+use std::sync::Arc;
 
-use super::{destination, LinkConverter};
+use super::{LinkConverter, SyncLinkConverter};
+use crate::fetch::{self, BlockingFetcher, Fetcher};
+use crate::product::{Marketplace, Product};
 use crate::{error::Error, Result};
 use async_trait::async_trait;
 use lazy_regex::regex_captures;
-use reqwest::Client;
 use url::Url;
 
-pub struct YouShop10(Client);
+const NAME: &str = "you_shop_10";
+
+/// Resolves `k.youshop10.com` share links by following their redirects.
+///
+/// Converts asynchronously with a [`Fetcher`], e.g. a [`reqwest::Client`], and on the
+/// calling thread with a [`BlockingFetcher`], e.g. a [`reqwest::blocking::Client`].
+/// The fetcher must follow redirects.
+pub struct YouShop10<F: ?Sized = dyn Fetcher>(Arc<F>);
 
 impl YouShop10 {
-    pub fn new(client: Client) -> Self {
-        YouShop10(client)
+    pub fn new(fetcher: Arc<dyn Fetcher>) -> Self {
+        YouShop10(fetcher)
+    }
+}
+
+impl YouShop10<dyn BlockingFetcher> {
+    pub fn blocking(fetcher: Arc<dyn BlockingFetcher>) -> Self {
+        YouShop10(fetcher)
+    }
+}
+
+/// Finds the product in the URL the share link redirected to.
+fn parse_redirect(url: &Url, final_url: &Url) -> Result<Product> {
+    match regex_captures!(r"itemID=(\d+)", final_url.as_str()) {
+        Some((_, item_id)) if !item_id.is_empty() => Product::new(Marketplace::Weidian, item_id),
+        _ => Err(Error::PageParse {
+            url: url.to_string(),
+            converter: NAME.to_string(),
+        }),
     }
 }
 
 #[async_trait]
 impl LinkConverter for YouShop10 {
+    fn name(&self) -> &str {
+        NAME
+    }
+
     fn can_convert(&self, url: &Url) -> bool {
         url.host_str() == Some("k.youshop10.com")
     }
 
-    async fn convert(&self, url: Url) -> Result<String> {
-        let resp = self.0.get(url.as_ref()).send().await?;
+    async fn convert(&self, url: Url) -> Result<Product> {
+        let resp = fetch::get(self.0.as_ref(), &url, NAME).await?;
 
-        match regex_captures!(r"itemID=(\d+)", &resp.url().as_str()) {
-            Some((_, item_id)) if !item_id.is_empty() => Ok(destination::weidian(item_id)),
-            _ => Err(Error::FailedToRedirectUrl { url }),
-        }
+        parse_redirect(&url, &resp.url)
+    }
+
+    fn is_network(&self) -> bool {
+        true
+    }
+}
+
+impl SyncLinkConverter for YouShop10<dyn BlockingFetcher> {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn can_convert(&self, url: &Url) -> bool {
+        url.host_str() == Some("k.youshop10.com")
+    }
+
+    fn convert(&self, url: Url) -> Result<Product> {
+        let resp = fetch::get_blocking(self.0.as_ref(), &url, NAME)?;
+
+        parse_redirect(&url, &resp.url)
+    }
+
+    fn is_network(&self) -> bool {
+        true
     }
 }
 
 // region:    --- Impl-Boilerplate
 
-impl fmt::Debug for YouShop10 {
+impl<F: ?Sized> fmt::Debug for YouShop10<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("YouShop10").finish()
     }
@@ -49,14 +100,33 @@ mod tests {
     type Result<T> = core::result::Result<T, Error>; // For tests.
 
     use reqwest::redirect::Policy;
+    use reqwest::Client;
 
     use super::*;
+    use crate::fetch::{FetchError, FetchRequest, FetchResponse};
+
+    /// Answers every request as if redirected to `0`, or with the status `1`.
+    #[derive(Debug)]
+    struct RecordedRedirect(&'static str, u16);
+
+    impl BlockingFetcher for RecordedRedirect {
+        fn fetch(&self, _: FetchRequest) -> core::result::Result<FetchResponse, FetchError> {
+            Ok(FetchResponse {
+                status: self.1,
+                url: Url::parse(self.0).expect("recorded URLs are valid"),
+                headers: Vec::new(),
+                body: Vec::new(),
+            })
+        }
+    }
 
     #[test]
     fn test_detects_convertable_url() -> Result<()> {
         // -- Setup & Fixtures
         let url = Url::parse("https://k.youshop10.com/-s=uo-wD?a=b&p=iphone&wfr=BuyercopyURL&share_relation=e0fd773efc74bec4_1651287329_1")?;
-        let converter = YouShop10::new(Client::builder().redirect(Policy::limited(10)).build()?);
+        let converter = YouShop10::new(Arc::new(
+            Client::builder().redirect(Policy::limited(10)).build()?,
+        ));
 
         // -- Exec
         let actual_value = converter.can_convert(&url);
@@ -71,14 +141,38 @@ mod tests {
     async fn test_url_conversion() -> Result<()> {
         // -- Setup & Fixtures
         let url = Url::parse("https://k.youshop10.com/-s=uo-wD?a=b&p=iphone&wfr=BuyercopyURL&share_relation=e0fd773efc74bec4_1651287329_1")?;
-        let converter = YouShop10::new(Client::builder().redirect(Policy::limited(10)).build()?);
+        let converter = YouShop10::new(Arc::new(
+            Client::builder().redirect(Policy::limited(10)).build()?,
+        ));
 
         // -- Exec
         let actual_converted_url = converter.convert(url).await?;
 
         // -- Check
         let expected_converted_url = "https://weidian.com/item.html?itemID=7301608442";
-        assert_eq!(actual_converted_url, expected_converted_url);
+        assert_eq!(actual_converted_url.to_string(), expected_converted_url);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_url_conversion_with_recorded_redirect() -> Result<()> {
+        // -- Setup & Fixtures
+        let url = Url::parse("https://k.youshop10.com/-s=uo-wD")?;
+        let redirect = "https://weidian.com/item.html?itemID=7301608442&wfr=BuyercopyURL";
+        let converter = YouShop10::blocking(Arc::new(RecordedRedirect(redirect, 200)));
+        let unavailable = YouShop10::blocking(Arc::new(RecordedRedirect(redirect, 503)));
+
+        // -- Exec
+        let actual = SyncLinkConverter::convert(&converter, url.clone())?;
+        let failed = SyncLinkConverter::convert(&unavailable, url);
+
+        // -- Check
+        assert_eq!(actual.id(), "7301608442");
+        assert!(matches!(
+            failed,
+            Err(crate::Error::UpstreamStatus { status: 503, .. })
+        ));
 
         Ok(())
     }
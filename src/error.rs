@@ -1,28 +1,334 @@
+use std::sync::Arc;
+
 use derive_more::{Display, From};
-use url::Url;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+#[cfg(feature = "network")]
+use crate::FetchError;
+use crate::Marketplace;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
-#[derive(Debug, From, Display)]
+#[derive(Debug, Clone, From, Display)]
 pub enum Error {
-    NonConvertableUrl {
-        given_url: Url,
+    // -- Conversion
+    #[display("no converter supports {url}")]
+    UnsupportedHost { url: String },
+    #[display("{converter}: {url} is missing the `{parameter}` parameter")]
+    MissingParameter {
+        url: String,
+        converter: String,
+        parameter: String,
+    },
+    #[display("{converter}: {url} has an unknown platform `{value}`")]
+    UnknownPlatform {
+        url: String,
+        converter: String,
+        value: String,
+    },
+    #[display("invalid {marketplace} product id `{id}`")]
+    InvalidProductId {
+        marketplace: Marketplace,
+        id: String,
+    },
+    #[display("{converter}: {url} responded with HTTP {status}")]
+    UpstreamStatus {
+        url: String,
+        converter: String,
+        status: u16,
+    },
+    #[display("{converter}: no product found in the page of {url}")]
+    PageParse { url: String, converter: String },
+    #[display("{converter}: {url} timed out")]
+    Timeout { url: String, converter: String },
+    #[display("{converter}: request to {url} failed: {message}")]
+    Http {
+        url: String,
+        converter: String,
+        message: String,
     },
-    FailedToRedirectUrl {
-        url: Url,
+    /// A conversion that failed after being retried, with the error of the last attempt,
+    /// whether or not it was retryable.
+    #[display("{last_error} (after {attempts} attempts)")]
+    Retried {
+        attempts: u32,
+        last_error: Box<Error>,
     },
 
+    // -- Agents & Setup
+    #[display("unknown agent: {name}")]
+    UnknownAgent { name: String },
+    #[display("unknown converter: {name}")]
+    UnknownConverter { name: String },
+    #[display("{name} has no blocking implementation")]
+    AsyncOnlyConverter { name: String },
+    #[display("{agent} does not support {marketplace} products")]
+    UnsupportedMarketplace {
+        agent: String,
+        marketplace: Marketplace,
+    },
+    #[display("invalid rules file {path}: {reason}")]
+    InvalidRulesFile { path: String, reason: String },
+    #[display("invalid rule #{index} ({name}): {reason}")]
+    InvalidRule {
+        index: usize,
+        name: String,
+        reason: String,
+    },
+    #[display("cache store {path}: {reason}")]
+    CacheStore { path: String, reason: String },
+
     // -- Externals
     #[from]
     InvalidUrl(url::ParseError),
-    #[from]
-    Reqwest(reqwest::Error),
-    #[from]
-    HeadlessChrome(anyhow::Error),
+    #[cfg(feature = "network")]
+    #[from(ignore)]
+    Reqwest(Arc<reqwest::Error>),
+    #[from(ignore)]
+    HeadlessChrome(Arc<anyhow::Error>),
+}
+
+impl Error {
+    /// Creates the error for a request of a network converter that got no response.
+    #[cfg(feature = "network")]
+    pub fn from_fetch(url: &str, converter: &str, error: FetchError) -> Self {
+        let url = url.to_string();
+        let converter = converter.to_string();
+
+        match error {
+            FetchError::Timeout => Error::Timeout { url, converter },
+            FetchError::Request(message) => Error::Http {
+                url,
+                converter,
+                message,
+            },
+        }
+    }
+
+    /// A stable, machine-readable code for the kind of error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::UnsupportedHost { .. } => "unsupported_host",
+            Error::MissingParameter { .. } => "missing_parameter",
+            Error::UnknownPlatform { .. } => "unknown_platform",
+            Error::InvalidProductId { .. } => "invalid_product_id",
+            Error::UpstreamStatus { .. } => "upstream_status",
+            Error::PageParse { .. } => "page_parse",
+            Error::Timeout { .. } => "timeout",
+            Error::Http { .. } => "http",
+            Error::Retried { .. } => "retried",
+            Error::UnknownAgent { .. } => "unknown_agent",
+            Error::UnknownConverter { .. } => "unknown_converter",
+            Error::AsyncOnlyConverter { .. } => "async_only_converter",
+            Error::UnsupportedMarketplace { .. } => "unsupported_marketplace",
+            Error::InvalidRulesFile { .. } => "invalid_rules_file",
+            Error::InvalidRule { .. } => "invalid_rule",
+            Error::CacheStore { .. } => "cache_store",
+            Error::InvalidUrl(_) => "invalid_url",
+            #[cfg(feature = "network")]
+            Error::Reqwest(_) => "reqwest",
+            Error::HeadlessChrome(_) => "headless_chrome",
+        }
+    }
+
+    /// The URL that failed to convert, if the error concerns one.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Error::UnsupportedHost { url }
+            | Error::MissingParameter { url, .. }
+            | Error::UnknownPlatform { url, .. }
+            | Error::UpstreamStatus { url, .. }
+            | Error::PageParse { url, .. }
+            | Error::Timeout { url, .. }
+            | Error::Http { url, .. } => Some(url),
+            Error::Retried { last_error, .. } => last_error.url(),
+            _ => None,
+        }
+    }
+
+    /// The name of the converter that failed, if the error comes from one.
+    pub fn converter(&self) -> Option<&str> {
+        match self {
+            Error::MissingParameter { converter, .. }
+            | Error::UnknownPlatform { converter, .. }
+            | Error::UpstreamStatus { converter, .. }
+            | Error::PageParse { converter, .. }
+            | Error::Timeout { converter, .. }
+            | Error::Http { converter, .. } => Some(converter),
+            Error::Retried { last_error, .. } => last_error.converter(),
+            _ => None,
+        }
+    }
+
+    /// The number of attempts made before failing, if the conversion was retried.
+    pub fn attempts(&self) -> Option<u32> {
+        match self {
+            Error::Retried { attempts, .. } => Some(*attempts),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes as the error's code and message, with its URL, converter and attempts
+/// when it has them, followed by the fields of its variant (e.g. `parameter`, `status`,
+/// or the nested `last_error` of a retried conversion).
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("Error", 8)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.serialize_field("url", &self.url())?;
+        error.serialize_field("converter", &self.converter())?;
+        error.serialize_field("attempts", &self.attempts())?;
+
+        match self {
+            Error::MissingParameter { parameter, .. } => {
+                error.serialize_field("parameter", parameter)?;
+            }
+            Error::UnknownPlatform { value, .. } => error.serialize_field("value", value)?,
+            Error::InvalidProductId { marketplace, id } => {
+                error.serialize_field("marketplace", marketplace)?;
+                error.serialize_field("id", id)?;
+            }
+            Error::UpstreamStatus { status, .. } => error.serialize_field("status", status)?,
+            // `message` is taken by the display message.
+            Error::Http { message, .. } => error.serialize_field("reason", message)?,
+            Error::Retried { last_error, .. } => {
+                error.serialize_field("last_error", last_error)?;
+            }
+            Error::UnknownAgent { name }
+            | Error::UnknownConverter { name }
+            | Error::AsyncOnlyConverter { name } => error.serialize_field("name", name)?,
+            Error::UnsupportedMarketplace { agent, marketplace } => {
+                error.serialize_field("agent", agent)?;
+                error.serialize_field("marketplace", marketplace)?;
+            }
+            Error::InvalidRulesFile { path, reason } | Error::CacheStore { path, reason } => {
+                error.serialize_field("path", path)?;
+                error.serialize_field("reason", reason)?;
+            }
+            Error::InvalidRule {
+                index,
+                name,
+                reason,
+            } => {
+                error.serialize_field("index", index)?;
+                error.serialize_field("name", name)?;
+                error.serialize_field("reason", reason)?;
+            }
+            _ => {}
+        }
+
+        error.end()
+    }
 }
 
 // region:    --- Error Boilerplate
 
-impl std::error::Error for Error {}
+#[cfg(feature = "network")]
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::Reqwest(Arc::new(error))
+    }
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(error: anyhow::Error) -> Self {
+        Error::HeadlessChrome(Arc::new(error))
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Retried { last_error, .. } => Some(last_error.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 // endregion: --- Error Boilerplate
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_and_context() {
+        // -- Setup & Fixtures
+        let error = Error::UnknownPlatform {
+            url: "https://cnfans.com/product?id=1&platform=JD".into(),
+            converter: "cnfans".into(),
+            value: "JD".into(),
+        };
+
+        // -- Check
+        assert_eq!(error.code(), "unknown_platform");
+        assert_eq!(error.converter(), Some("cnfans"));
+        assert_eq!(
+            error.url(),
+            Some("https://cnfans.com/product?id=1&platform=JD")
+        );
+    }
+
+    #[test]
+    fn test_error_serializes_with_context() -> serde_json::Result<()> {
+        // -- Setup & Fixtures
+        let error = Error::Retried {
+            attempts: 3,
+            last_error: Box::new(Error::UpstreamStatus {
+                url: "https://m.tb.cn/h.abc".into(),
+                converter: "mobile_taobao".into(),
+                status: 503,
+            }),
+        };
+
+        // -- Exec
+        let json = serde_json::to_value(&error)?;
+
+        // -- Check
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "code": "retried",
+                "message": "mobile_taobao: https://m.tb.cn/h.abc responded with HTTP 503 (after 3 attempts)",
+                "url": "https://m.tb.cn/h.abc",
+                "converter": "mobile_taobao",
+                "attempts": 3,
+                "last_error": {
+                    "code": "upstream_status",
+                    "message": "mobile_taobao: https://m.tb.cn/h.abc responded with HTTP 503",
+                    "url": "https://m.tb.cn/h.abc",
+                    "converter": "mobile_taobao",
+                    "attempts": null,
+                    "status": 503,
+                },
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_serializes_variant_fields() -> serde_json::Result<()> {
+        // -- Setup & Fixtures
+        let error = Error::InvalidProductId {
+            marketplace: Marketplace::Ali1688,
+            id: "a b".into(),
+        };
+
+        // -- Exec
+        let json = serde_json::to_value(&error)?;
+
+        // -- Check
+        assert_eq!(json["code"], "invalid_product_id");
+        assert_eq!(json["marketplace"], "ali1688");
+        assert_eq!(json["id"], "a b");
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
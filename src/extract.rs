@@ -0,0 +1,171 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use lazy_regex::regex;
+
+/// A link found in a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FoundLink<'a> {
+    /// Byte span of the link in the text.
+    pub span: Range<usize>,
+    /// The link as written in the text.
+    pub text: &'a str,
+    /// The link with a scheme, ready to be parsed.
+    pub url: Cow<'a, str>,
+}
+
+/// Finds the links in a text.
+///
+/// Besides `http(s)://` links this recognizes scheme-less `www.` and share links
+/// (`m.tb.cn/...`), stops links at whitespace and non-ASCII characters such as CJK
+/// punctuation, splits concatenated links, and trims trailing punctuation and closing
+/// brackets that are not part of the link.
+pub(crate) fn find_links(text: &str) -> Vec<FoundLink<'_>> {
+    let starts = regex!(r"(?i)https?://|www\.|(?:m\.tb\.cn|e\.tb\.cn|k\.youshop10\.com)/");
+
+    let mut links = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(start) = starts.find_at(text, search_from) {
+        let begin = start.start();
+        search_from = start.end();
+
+        // A scheme-less match inside a word or an address (e.g. `xwww.`, `me@www.`) is not a start.
+        let scheme_less = !start.as_str().contains("://");
+        let preceding = text[..begin].chars().next_back();
+        if scheme_less
+            && preceding.is_some_and(|c| c.is_ascii_alphanumeric() || "./-_@:".contains(c))
+        {
+            continue;
+        }
+
+        let end = link_end(text, begin, start.end());
+        if end <= start.end() {
+            continue;
+        }
+
+        let link = &text[begin..end];
+        let url = if scheme_less {
+            Cow::Owned(format!("https://{link}"))
+        } else {
+            Cow::Borrowed(link)
+        };
+
+        links.push(FoundLink {
+            span: begin..end,
+            text: link,
+            url,
+        });
+        search_from = end;
+    }
+
+    links
+}
+
+/// Finds the end of the link starting at `begin`, whose prefix ends at `prefix_end`.
+fn link_end(text: &str, begin: usize, prefix_end: usize) -> usize {
+    // Take everything up to whitespace, a non-ASCII character or a delimiter.
+    let mut end = text[prefix_end..]
+        .find(|c: char| c.is_whitespace() || !c.is_ascii() || "<>\"`|".contains(c))
+        .map_or(text.len(), |offset| prefix_end + offset);
+
+    // Split concatenated links, but keep links nested in a parameter or path
+    // (e.g. `?to=https://...`, `/web/https://...`) in the link.
+    let concatenated = regex!(r"(?i)https?://")
+        .find_iter(&text[prefix_end..end])
+        .map(|m| prefix_end + m.start())
+        .find(|&start| !text[..start].ends_with(['=', '/', '%', '?', '&', '#', ':']));
+    if let Some(start) = concatenated {
+        end = start;
+    }
+
+    // Trim trailing punctuation and unbalanced closing brackets.
+    loop {
+        let link = &text[begin..end];
+        let Some(last) = link.chars().next_back() else {
+            return end;
+        };
+
+        let unbalanced = match last {
+            ')' => link.matches('(').count() < link.matches(')').count(),
+            ']' => link.matches('[').count() < link.matches(']').count(),
+            '}' => link.matches('{').count() < link.matches('}').count(),
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '*' | '(' | '[' | '{' => true,
+            _ => false,
+        };
+        if !unbalanced || end <= prefix_end {
+            return end;
+        }
+        end -= last.len_utf8();
+    }
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(text: &str) -> Vec<String> {
+        find_links(text)
+            .into_iter()
+            .map(|link| link.url.into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_find_links() {
+        // -- Patterns
+        let test_cases: &[(&str, &[&str])] = &[
+            (
+                "Hello https://www.rust-lang.org/ friend https://crates.io/.",
+                &["https://www.rust-lang.org/", "https://crates.io/"],
+            ),
+            (
+                "see (https://en.wikipedia.org/wiki/Rust_(programming_language)), ok?",
+                &["https://en.wikipedia.org/wiki/Rust_(programming_language)"],
+            ),
+            (
+                "[cnfans](https://cnfans.com/product?id=1&platform=TAOBAO)",
+                &["https://cnfans.com/product?id=1&platform=TAOBAO"],
+            ),
+            (
+                "【淘宝】https://m.tb.cn/h.TjKAehX?tk=Jrdnecne92w「商品」，点击链接",
+                &["https://m.tb.cn/h.TjKAehX?tk=Jrdnecne92w"],
+            ),
+            (
+                "两个链接https://a.example/1https://b.example/2。",
+                &["https://a.example/1", "https://b.example/2"],
+            ),
+            (
+                "scheme-less www.example.com/page, and m.tb.cn/h.abc!",
+                &["https://www.example.com/page", "https://m.tb.cn/h.abc"],
+            ),
+            ("mail me at someone@www.example.com", &[]),
+            (
+                "https://www.example.com/redirect?to=https://item.taobao.com/item.htm?id=1",
+                &["https://www.example.com/redirect?to=https://item.taobao.com/item.htm?id=1"],
+            ),
+        ];
+
+        for (text, expected) in test_cases {
+            assert_eq!(urls(text), *expected, "in {text:?}");
+        }
+    }
+
+    #[test]
+    fn test_find_links_spans() {
+        // -- Setup & Fixtures
+        let text = "「https://m.tb.cn/h.abc」";
+
+        // -- Exec
+        let links = find_links(text);
+
+        // -- Check
+        assert_eq!(links.len(), 1);
+        assert_eq!(&text[links[0].span.clone()], "https://m.tb.cn/h.abc");
+        assert_eq!(links[0].text, "https://m.tb.cn/h.abc");
+    }
+}
+
+// endregion: --- Tests
@@ -0,0 +1,188 @@
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use derive_more::Display;
+use reqwest::header::HeaderMap;
+use url::Url;
+
+use crate::{Error, Result};
+
+/// A GET request sent by a network converter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchRequest {
+    pub url: Url,
+    /// Extra request headers, as name and value pairs.
+    pub headers: Vec<(String, String)>,
+}
+
+impl FetchRequest {
+    /// A request to `url` without extra headers.
+    pub fn get(url: Url) -> Self {
+        Self {
+            url,
+            headers: Vec::new(),
+        }
+    }
+}
+
+/// The response to a [`FetchRequest`], once redirects are followed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchResponse {
+    pub status: u16,
+    /// The URL of the response, after any redirect.
+    pub url: Url,
+    /// Response headers, as name and value pairs.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl FetchResponse {
+    /// The body as text, with invalid UTF-8 replaced.
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.body)
+    }
+}
+
+/// Why a [`Fetcher`] got no response.
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum FetchError {
+    #[display("timed out")]
+    Timeout,
+    /// The request failed without a response, e.g. on a refused connection.
+    #[display("{_0}")]
+    Request(String),
+}
+
+impl std::error::Error for FetchError {}
+
+/// The HTTP transport of the network converters, implemented by [`reqwest::Client`].
+///
+/// Implement it to route requests through another HTTP stack or middleware, or to
+/// answer with recorded responses, and set it with [`crate::ConverterBuilder::fetcher`].
+/// Redirects are expected to be followed, and error statuses to be returned as responses.
+#[async_trait]
+pub trait Fetcher: Send + Sync + Debug {
+    /// Sends the request and returns the final response, whatever its status.
+    async fn fetch(&self, request: FetchRequest)
+        -> core::result::Result<FetchResponse, FetchError>;
+}
+
+/// The blocking counterpart of [`Fetcher`], used by the network converters of a
+/// [`crate::BlockingConverter`] and implemented by [`reqwest::blocking::Client`].
+pub trait BlockingFetcher: Send + Sync + Debug {
+    /// See [`Fetcher::fetch`].
+    fn fetch(&self, request: FetchRequest) -> core::result::Result<FetchResponse, FetchError>;
+}
+
+/// Fetches the URL for the named converter, failing on error statuses.
+pub(crate) async fn get(
+    fetcher: &dyn Fetcher,
+    url: &Url,
+    converter: &str,
+) -> Result<FetchResponse> {
+    let response = fetcher.fetch(FetchRequest::get(url.clone())).await;
+
+    check(url, converter, response)
+}
+
+/// Blocking [`get`].
+pub(crate) fn get_blocking(
+    fetcher: &dyn BlockingFetcher,
+    url: &Url,
+    converter: &str,
+) -> Result<FetchResponse> {
+    let response = fetcher.fetch(FetchRequest::get(url.clone()));
+
+    check(url, converter, response)
+}
+
+fn check(
+    url: &Url,
+    converter: &str,
+    response: core::result::Result<FetchResponse, FetchError>,
+) -> Result<FetchResponse> {
+    let response = response.map_err(|e| Error::from_fetch(url.as_str(), converter, e))?;
+
+    if response.status >= 400 {
+        return Err(Error::UpstreamStatus {
+            url: url.to_string(),
+            converter: converter.to_string(),
+            status: response.status,
+        });
+    }
+
+    Ok(response)
+}
+
+// region:    --- Reqwest
+
+#[async_trait]
+impl Fetcher for reqwest::Client {
+    async fn fetch(
+        &self,
+        request: FetchRequest,
+    ) -> core::result::Result<FetchResponse, FetchError> {
+        let mut builder = self.get(request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let url = response.url().clone();
+        let headers = to_pairs(response.headers());
+        let body = response.bytes().await?.to_vec();
+
+        Ok(FetchResponse {
+            status,
+            url,
+            headers,
+            body,
+        })
+    }
+}
+
+impl BlockingFetcher for reqwest::blocking::Client {
+    fn fetch(&self, request: FetchRequest) -> core::result::Result<FetchResponse, FetchError> {
+        let mut builder = self.get(request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send()?;
+        let status = response.status().as_u16();
+        let url = response.url().clone();
+        let headers = to_pairs(response.headers());
+        let body = response.bytes()?.to_vec();
+
+        Ok(FetchResponse {
+            status,
+            url,
+            headers,
+            body,
+        })
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            FetchError::Timeout
+        } else {
+            FetchError::Request(error.to_string())
+        }
+    }
+}
+
+fn to_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+// endregion: --- Reqwest
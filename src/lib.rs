@@ -1,111 +1,280 @@
 // region:    --- Modules
 
-mod converters;
+mod blocking;
+mod builder;
+#[cfg(feature = "network")]
+mod cache;
+#[cfg(feature = "network")]
+mod converter;
+pub mod converters;
 mod error;
+mod extract;
+#[cfg(feature = "network")]
+mod fetch;
+mod product;
+#[cfg(feature = "network")]
+mod rate_limit;
+#[cfg(feature = "network")]
+mod retry;
+mod rewrite;
+#[cfg(feature = "server")]
+pub mod server;
 
-use converters::LinkConverter;
-use lazy_regex::regex_captures_iter;
-use reqwest::{redirect::Policy, Client};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
 use url::Url;
 
+use converters::rule::{Rule, RuleConverter};
+use converters::Registered;
+
 // -- Flatten
 
+pub use blocking::BlockingConverter;
+pub use builder::{ConverterBuilder, BUILTIN_CONVERTERS};
+#[cfg(feature = "network")]
+pub use cache::{
+    CacheRecord, ConversionCache, DiskCache, MemoryCache, DEFAULT_DISK_TTL, DEFAULT_NEGATIVE_TTL,
+    DEFAULT_TTL,
+};
+#[cfg(feature = "network")]
+pub use converter::{Converter, DEFAULT_CONCURRENCY, DEFAULT_TIMEOUT};
+pub use converters::rule_file::RulesFormat;
 pub use error::{Error, Result};
+#[cfg(feature = "network")]
+pub use fetch::{BlockingFetcher, FetchError, FetchRequest, FetchResponse, Fetcher};
+pub use product::{Marketplace, Product};
+#[cfg(feature = "network")]
+pub use rate_limit::RateLimit;
+#[cfg(feature = "network")]
+pub use retry::{RetryPolicy, DEFAULT_RETRYABLE_STATUSES};
+pub use rewrite::{Replacement, RewriteMode, RewrittenText};
 
 // endregion: --- Modules
 
-/// Main Converter struct holding registered URL conversion strategies.
-#[derive(Debug)]
-pub struct Converter {
-    converters: Vec<Box<dyn LinkConverter>>,
+/// The distinct URLs of the links, and the index of each link's URL among them.
+fn unique_links<'a>(links: &'a [extract::FoundLink]) -> (Vec<&'a str>, Vec<usize>) {
+    let mut unique: Vec<&str> = Vec::new();
+    let mut indexes: HashMap<&str, usize> = HashMap::new();
+    let positions = links
+        .iter()
+        .map(|link| {
+            *indexes.entry(&link.url).or_insert_with(|| {
+                unique.push(&link.url);
+                unique.len() - 1
+            })
+        })
+        .collect();
+
+    (unique, positions)
 }
 
-impl Converter {
-    /// Creates a new Converter instance with default HTTP client settings.
-    ///
-    /// # Errors
-    /// Returns `Error` if the underlying HTTP client fails to build.
-    pub fn new() -> Result<Self> {
-        let client = Client::builder().redirect(Policy::limited(10)).build()?;
-        Ok(Self {
-            converters: Self::init_converters(client),
+/// Pairs the links with the conversions of their URL, see [`unique_links`].
+fn bulk_entries(
+    links: Vec<extract::FoundLink>,
+    positions: &[usize],
+    conversions: &[(Option<&str>, Result<Product>)],
+) -> Vec<BulkEntry> {
+    links
+        .into_iter()
+        .zip(positions)
+        .map(|(link, &index)| {
+            let (converter, outcome) = conversions[index].clone();
+            BulkEntry {
+                input: link.text.to_string(),
+                span: link.span,
+                converter: converter.map(str::to_string),
+                outcome,
+            }
         })
-    }
+        .collect()
+}
+
+/// Registers the rules as converters. A rule replaces the converter of the same name,
+/// other rules are added after the registered converters.
+fn register_rules<C: Registered + ?Sized>(converters: &mut Vec<Box<C>>, rules: Vec<Rule>) {
+    for rule in rules {
+        let converter = C::from_rule(RuleConverter::new(rule));
 
-    /// Creates a new Converter using a pre-configured HTTP client.
-    ///
-    /// Its suggested to set a redirect Policy.
-    pub fn from_client(client: Client) -> Self {
-        Self {
-            converters: Self::init_converters(client),
+        match converters
+            .iter()
+            .position(|existing| existing.name() == converter.name())
+        {
+            Some(index) => converters[index] = converter,
+            None => converters.push(converter),
         }
     }
+}
 
-    /// Initializes the converters
-    fn init_converters(client: Client) -> Vec<Box<dyn LinkConverter>> {
-        vec![
-            Box::new(converters::you_shop_10::YouShop10::new(client.clone())),
-            Box::new(converters::mobile_taobao::MobileTaobao::new(client.clone())),
-            Box::new(converters::mobile_intl_taobao::MobileIntlTaobao),
-            Box::new(converters::cssbuy::CSSBuy::new()),
-            Box::new(converters::lovegobuy::LoveGoBuy::new()),
-            Box::new(converters::mulebuy::MuleBuy::new()),
-            Box::new(converters::ootdbuy::OotdBuy::new()),
-            Box::new(converters::cnfans::CnFans::new()),
-            Box::new(converters::orientdig::OrientDig::new()),
-            Box::new(converters::oopbuy::OopBuy::new()),
-            Box::new(converters::joyabuy::JoyaBuy::new()),
-            Box::new(converters::acbuy::AcBuy::new()),
-        ]
-    }
+/// Builds the link of the named agent for a product, see [`BlockingConverter::agent_link`].
+fn agent_link<C: Registered + ?Sized>(
+    converters: &[Box<C>],
+    product: &Product,
+    agent: &str,
+) -> Result<Url> {
+    let converter = converters
+        .iter()
+        .find(|converter| converter.name() == agent)
+        .ok_or_else(|| Error::UnknownAgent {
+            name: agent.to_string(),
+        })?;
 
-    /// Converts a single URL using the first applicable converter.
-    ///
-    /// # Parameters
-    /// - `url`: The URL to be converted
-    ///
-    /// # Errors
-    /// Returns `Error::NonConvertableUrl` if no registered converter can handle the URL.
-    pub async fn convert_one(&self, url: Url) -> Result<String> {
-        for converter in &self.converters {
-            if converter.can_convert(&url) {
-                return converter.convert(url).await;
-            }
-        }
+    converter
+        .agent_link(product)
+        .ok_or_else(|| Error::UnsupportedMarketplace {
+            agent: agent.to_string(),
+            marketplace: product.marketplace(),
+        })
+}
+
+/// Converts a raw marketplace link into the named agent's link, see
+/// [`BlockingConverter::to_agent`].
+fn raw_agent_link<C: Registered + ?Sized>(
+    converters: &[Box<C>],
+    raw_url: Url,
+    agent: &str,
+) -> Result<Url> {
+    let Some(product) = converters::raw::parse(&raw_url) else {
+        return Err(Error::UnsupportedHost {
+            url: raw_url.to_string(),
+        });
+    };
+
+    agent_link(converters, &product, agent)
+}
 
-        Err(Error::NonConvertableUrl { given_url: url })
+/// Replaces the products of a bulk conversion with the named agent's links.
+fn to_agent_links<C: Registered + ?Sized>(
+    converters: &[Box<C>],
+    result: ConversionResult,
+    agent: &str,
+) -> ConversionResult<Url> {
+    let entries = result
+        .entries
+        .into_iter()
+        .map(|entry| entry.and_then(|product| agent_link(converters, &product, agent)))
+        .collect();
+
+    ConversionResult { entries }
+}
+
+/// Rewrites the text with the outcomes of its bulk conversion.
+fn rewrite_converted(
+    text: &str,
+    mode: RewriteMode,
+    converted: Result<ConversionResult>,
+) -> RewrittenText {
+    let conversions = converted
+        .map(|res| res.entries)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| (entry.span, entry.outcome))
+        .collect();
+
+    rewrite::rewrite(text, mode, conversions)
+}
+
+/// Result container for bulk conversion operations.
+#[derive(Debug, serde::Serialize)]
+pub struct ConversionResult<T = Product> {
+    /// One entry per link, in the order the links were found
+    pub entries: Vec<BulkEntry<T>>,
+}
+
+impl<T> ConversionResult<T> {
+    /// Successful conversions in the order they were found
+    pub fn successes(&self) -> Vec<&T> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.outcome.as_ref().ok())
+            .collect()
     }
 
-    /// Processes text content to find and convert all HTTP/HTTPS URLs. Returns a [`ConversionResult`].
-    ///
-    /// # Note
-    /// URL detection uses a simple regex pattern (`https?://[^\s]+`). Some valid URLs
-    /// might not be detected if they contain spaces or unusual formatting.
-    pub async fn convert_bulk(&self, text: &str) -> Result<ConversionResult> {
-        let mut successes = Vec::new();
-        let mut errors = Vec::new();
-
-        let iter = regex_captures_iter!(r"(https?://[^\s]+)", text);
-
-        for (_, [url]) in iter.map(|c| c.extract()) {
-            match Url::parse(url) {
-                Ok(parsed_url) => match self.convert_one(parsed_url).await {
-                    Ok(converted) => successes.push(converted),
-                    Err(e) => errors.push((url.to_string(), e)),
-                },
-                Err(e) => errors.push((url.to_string(), e.into())),
-            }
+    /// Conversion failures with original URL and error details
+    pub fn errors(&self) -> Vec<(&str, &Error)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match &entry.outcome {
+                Ok(_) => None,
+                Err(e) => Some((entry.input.as_str(), e)),
+            })
+            .collect()
+    }
+}
+
+impl ConversionResult {
+    /// Collapses the successful entries by product identity (marketplace and item id),
+    /// in the order the products were first found.
+    pub fn group_by_product(&self) -> Vec<ProductGroup<'_>> {
+        let mut groups: Vec<ProductGroup> = Vec::new();
+        let mut indexes: HashMap<&Product, usize> = HashMap::new();
+
+        for entry in &self.entries {
+            let Ok(product) = &entry.outcome else {
+                continue;
+            };
+            let index = *indexes.entry(product).or_insert_with(|| {
+                groups.push(ProductGroup {
+                    product,
+                    entries: Vec::new(),
+                });
+                groups.len() - 1
+            });
+            groups[index].entries.push(entry);
         }
 
-        Ok(ConversionResult { successes, errors })
+        groups
     }
 }
 
-/// Result container for bulk conversion operations.
+/// The entries of a bulk conversion that resolved to the same product.
 #[derive(Debug)]
-pub struct ConversionResult {
-    /// Successfully converted URLs in the order they were found
-    pub successes: Vec<String>,
-    /// Conversion failures with original URL and error details
-    pub errors: Vec<(String, Error)>,
+pub struct ProductGroup<'a> {
+    /// The shared product
+    pub product: &'a Product,
+    /// The entries converted to the product, in input order
+    pub entries: Vec<&'a BulkEntry>,
+}
+
+/// The conversion of one link found in a text.
+#[derive(Debug, Clone)]
+pub struct BulkEntry<T = Product> {
+    /// The link as written in the text
+    pub input: String,
+    /// Byte span of the link in the text
+    pub span: Range<usize>,
+    /// Name of the converter that handled the link, `None` if no converter supports it
+    pub converter: Option<String>,
+    /// The conversion outcome
+    pub outcome: Result<T>,
+}
+
+impl<T> BulkEntry<T> {
+    fn and_then<U>(self, f: impl FnOnce(T) -> Result<U>) -> BulkEntry<U> {
+        BulkEntry {
+            input: self.input,
+            span: self.span,
+            converter: self.converter,
+            outcome: self.outcome.and_then(f),
+        }
+    }
+}
+
+/// Serializes the outcome as an `output` or an `error` field, the other one being null.
+impl<T: Serialize> Serialize for BulkEntry<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let (output, error) = match &self.outcome {
+            Ok(output) => (Some(output), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        let mut entry = serializer.serialize_struct("BulkEntry", 5)?;
+        entry.serialize_field("input", &self.input)?;
+        entry.serialize_field("span", &self.span)?;
+        entry.serialize_field("converter", &self.converter)?;
+        entry.serialize_field("output", &output)?;
+        entry.serialize_field("error", &error)?;
+        entry.end()
+    }
 }
@@ -0,0 +1,367 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use futures::StreamExt;
+use link_converter::{BulkEntry, ConversionResult, Converter, Product};
+use url::Url;
+
+const USAGE: &str = "\
+Usage: link-converter [OPTIONS] [URL]...
+
+Converts agent, share and marketplace links into marketplace links, or into an agent's
+links with --agent. URLs given as arguments are converted one by one; the links found in
+files (or in stdin when neither URLs nor files are given) are converted in bulk.
+
+Options:
+  -f, --file <PATH>      Converts the links found in a file, `-` for stdin
+  -a, --agent <NAME>     Converts into the links of the named agent, e.g. cnfans
+      --format <FORMAT>  Output format, see below [default: text]
+  -h, --help             Prints this help
+
+Formats:
+  text    The converted links, one per line, with the failures on stderr
+  json    The full result, an object with the entries of every link
+  ndjson  One entry per line, written as each link is converted, in completion order
+  csv     The input, output, converter and error columns, after a header row
+
+Exit status: 0 if every link converted, 1 if a link failed, 2 on usage or I/O errors.";
+
+/// Exit status when a link failed to convert.
+const EXIT_FAILED_LINK: u8 = 1;
+
+/// Exit status on usage or I/O errors.
+const EXIT_USAGE: u8 = 2;
+
+/// How the conversions are written to stdout.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "unknown format {format}, expected text, json, ndjson or csv"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Args {
+    urls: Vec<String>,
+    files: Vec<PathBuf>,
+    agent: Option<String>,
+    format: Format,
+    help: bool,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("{name} requires a value"))
+            };
+
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "-f" | "--file" => parsed.files.push(value(&arg)?.into()),
+                "-a" | "--agent" => parsed.agent = Some(value(&arg)?),
+                "--format" => parsed.format = value(&arg)?.parse()?,
+                "-" => parsed.files.push(arg.into()),
+                "--" => parsed.urls.extend(args.by_ref()),
+                _ if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+                _ => parsed.urls.push(arg),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// The files to read links from; stdin when neither URLs nor files are given.
+    fn sources(&self) -> Vec<PathBuf> {
+        if self.urls.is_empty() && self.files.is_empty() {
+            return vec![PathBuf::from("-")];
+        }
+        self.files.clone()
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("link-converter: {message}\n\n{USAGE}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    if args.help {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let converter = match Converter::new() {
+        Ok(converter) => converter,
+        Err(e) => {
+            eprintln!("link-converter: {e}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let agent = args.agent.as_deref();
+    let mut report = Report::new(args.format);
+    for url in &args.urls {
+        report.write(convert_one(&converter, url, agent).await);
+    }
+
+    for source in args.sources() {
+        let text = match read_source(&source) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("link-converter: {}: {e}", source.display());
+                return ExitCode::from(EXIT_USAGE);
+            }
+        };
+
+        // NDJSON lines are written as the links convert, the other formats keep input order.
+        if args.format == Format::Ndjson {
+            let mut entries = converter.convert_stream(&text);
+            while let Some(entry) = entries.next().await {
+                report.write(to_output(&converter, entry, agent));
+            }
+            continue;
+        }
+        match converter.convert_bulk(&text).await {
+            Ok(result) => {
+                for entry in result.entries {
+                    report.write(to_output(&converter, entry, agent));
+                }
+            }
+            Err(e) => {
+                eprintln!("link-converter: {}: {e}", source.display());
+                return ExitCode::from(EXIT_USAGE);
+            }
+        }
+    }
+
+    report.finish()
+}
+
+async fn convert_one(converter: &Converter, input: &str, agent: Option<&str>) -> BulkEntry<Url> {
+    let (name, outcome) = match Url::parse(input) {
+        Ok(url) => {
+            let name = converter.converter_for(&url).map(str::to_string);
+            let outcome = converter.convert_one(url).await;
+            let outcome = outcome.and_then(|product| output(converter, &product, agent));
+            (name, outcome)
+        }
+        Err(e) => (None, Err(e.into())),
+    };
+
+    BulkEntry {
+        input: input.to_string(),
+        span: 0..input.len(),
+        converter: name,
+        outcome,
+    }
+}
+
+/// Maps a converted entry to its output link.
+fn to_output(converter: &Converter, entry: BulkEntry, agent: Option<&str>) -> BulkEntry<Url> {
+    BulkEntry {
+        input: entry.input,
+        span: entry.span,
+        converter: entry.converter,
+        outcome: entry
+            .outcome
+            .and_then(|product| output(converter, &product, agent)),
+    }
+}
+
+/// The product's agent link if an agent was requested, its marketplace link otherwise.
+fn output(
+    converter: &Converter,
+    product: &Product,
+    agent: Option<&str>,
+) -> link_converter::Result<Url> {
+    match agent {
+        Some(agent) => converter.agent_link(product, agent),
+        None => Ok(product.url().clone()),
+    }
+}
+
+fn read_source(source: &PathBuf) -> io::Result<String> {
+    if source.as_os_str() == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        fs::read_to_string(source)
+    }
+}
+
+/// Writes the conversions to stdout as they come, in the chosen [`Format`].
+struct Report {
+    format: Format,
+    /// The entries of the JSON document, written once every input is converted.
+    entries: Vec<BulkEntry<Url>>,
+    failed: bool,
+}
+
+impl Report {
+    fn new(format: Format) -> Self {
+        if format == Format::Csv {
+            println!("{}", csv_row(&["input", "output", "converter", "error"]));
+        }
+
+        Self {
+            format,
+            entries: Vec::new(),
+            failed: false,
+        }
+    }
+
+    fn write(&mut self, entry: BulkEntry<Url>) {
+        let mut stdout = io::stdout().lock();
+        self.failed |= entry.outcome.is_err();
+
+        // A closed stdout (e.g. `| head`) is not an error worth reporting.
+        _ = match self.format {
+            Format::Text => match &entry.outcome {
+                Ok(output) => writeln!(stdout, "{output}"),
+                Err(e) => {
+                    eprintln!("link-converter: {}: {e}", entry.input);
+                    Ok(())
+                }
+            },
+            Format::Json => {
+                self.entries.push(entry);
+                Ok(())
+            }
+            Format::Ndjson => serde_json::to_writer(&mut stdout, &entry)
+                .map_err(io::Error::from)
+                .and_then(|_| writeln!(stdout))
+                .and_then(|_| stdout.flush()),
+            Format::Csv => {
+                let (output, error) = match &entry.outcome {
+                    Ok(output) => (output.to_string(), String::new()),
+                    Err(e) => (String::new(), e.to_string()),
+                };
+                let converter = entry.converter.as_deref().unwrap_or_default();
+                let row = csv_row(&[&entry.input, &output, converter, &error]);
+                writeln!(stdout, "{row}")
+            }
+        };
+    }
+
+    /// Writes the JSON document, if that is the format, and returns the exit status.
+    fn finish(self) -> ExitCode {
+        if self.format == Format::Json {
+            let result = ConversionResult {
+                entries: self.entries,
+            };
+            let mut stdout = io::stdout().lock();
+            _ = serde_json::to_writer_pretty(&mut stdout, &result)
+                .map_err(io::Error::from)
+                .and_then(|_| writeln!(stdout));
+        }
+
+        if self.failed {
+            ExitCode::from(EXIT_FAILED_LINK)
+        } else {
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// Joins the fields into a CSV row, quoting the fields that need it.
+fn csv_row(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> core::result::Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() -> Result<()> {
+        // -- Exec
+        let args = parse(&[
+            "-a",
+            "cnfans",
+            "https://a.example/1",
+            "--file",
+            "links.txt",
+            "-",
+        ])?;
+
+        // -- Check
+        assert_eq!(args.agent.as_deref(), Some("cnfans"));
+        assert_eq!(args.urls, ["https://a.example/1"]);
+        assert_eq!(args.files, [PathBuf::from("links.txt"), PathBuf::from("-")]);
+        assert_eq!(parse(&[])?.sources(), [PathBuf::from("-")]);
+        assert_eq!(parse(&["--format", "ndjson"])?.format, Format::Ndjson);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_parse_args() {
+        // -- Check
+        assert_eq!(parse(&["--agent"]), Err("--agent requires a value".into()));
+        assert_eq!(parse(&["--nope"]), Err("unknown option --nope".into()));
+        assert_eq!(
+            parse(&["--format", "xml"]),
+            Err("unknown format xml, expected text, json, ndjson or csv".into())
+        );
+    }
+
+    #[test]
+    fn test_csv_row_quotes_fields() {
+        // -- Exec
+        let row = csv_row(&["https://a.example/?a=1,2", "say \"hi\"", ""]);
+
+        // -- Check
+        assert_eq!(row, r#""https://a.example/?a=1,2","say ""hi""","#);
+    }
+}
+
+// endregion: --- Tests
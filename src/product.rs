@@ -0,0 +1,154 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::converters::destination;
+use crate::{Error, Result};
+
+/// The marketplace a converted link points to.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Marketplace {
+    Taobao,
+    Weidian,
+    #[display("1688")]
+    Ali1688,
+    Goofish,
+    /// A Taobao storefront, the product id is the shop id.
+    TaobaoShop,
+}
+
+/// A product resolved from a link, identified by its marketplace and item id.
+///
+/// Deserializing goes through [`Product::new`], so the id is validated and the URL rebuilt.
+#[derive(Debug, Display, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "ProductData")]
+#[display("{url}")]
+pub struct Product {
+    marketplace: Marketplace,
+    id: String,
+    url: Url,
+}
+
+impl Product {
+    /// Creates a product and builds its canonical marketplace URL.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidProductId` if the id is not a non-empty string of ASCII digits.
+    pub fn new(marketplace: Marketplace, id: impl Into<String>) -> Result<Self> {
+        let id = id.into();
+        if !is_product_id(&id) {
+            return Err(Error::InvalidProductId { marketplace, id });
+        }
+
+        let url = match marketplace {
+            Marketplace::Taobao => destination::taobao(&id),
+            Marketplace::Weidian => destination::weidian(&id),
+            Marketplace::Ali1688 => destination::ali_1688(&id),
+            Marketplace::Goofish => destination::goofish(&id),
+            Marketplace::TaobaoShop => destination::taobao_shop(&id),
+        };
+        let url = match Url::parse(&url) {
+            Ok(url) => url,
+            Err(_) => return Err(Error::InvalidProductId { marketplace, id }),
+        };
+
+        Ok(Self {
+            marketplace,
+            id,
+            url,
+        })
+    }
+
+    pub fn marketplace(&self) -> Marketplace {
+        self.marketplace
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The canonical marketplace URL of the product.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+}
+
+/// The serialized fields a [`Product`] is deserialized from, its URL is ignored.
+#[derive(Deserialize)]
+struct ProductData {
+    marketplace: Marketplace,
+    id: String,
+}
+
+impl TryFrom<ProductData> for Product {
+    type Error = Error;
+
+    fn try_from(data: ProductData) -> Result<Self> {
+        Product::new(data.marketplace, data.id)
+    }
+}
+
+/// Whether the value can be a marketplace product id: non-empty ASCII digits.
+pub(crate) fn is_product_id(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_product_builds_canonical_url() -> Result<()> {
+        // -- Setup & Fixtures
+        let product = Product::new(Marketplace::Ali1688, "681296637536")?;
+
+        // -- Check
+        assert_eq!(product.marketplace(), Marketplace::Ali1688);
+        assert_eq!(product.id(), "681296637536");
+        assert_eq!(
+            product.url().as_str(),
+            "https://detail.1688.com/offer/681296637536.html"
+        );
+        assert_eq!(product.to_string(), product.url().as_str());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_product_deserializes_through_new() -> serde_json::Result<()> {
+        // -- Setup & Fixtures
+        let product = Product::new(Marketplace::Weidian, "7322752149").expect("valid product");
+
+        // -- Exec
+        let round_trip: Product = serde_json::from_value(serde_json::to_value(&product)?)?;
+        let without_url: Product =
+            serde_json::from_str(r#"{"marketplace":"weidian","id":"7322752149"}"#)?;
+        let invalid = serde_json::from_str::<Product>(r#"{"marketplace":"weidian","id":"abc"}"#);
+
+        // -- Check
+        assert_eq!(round_trip, product);
+        assert_eq!(without_url, product);
+        assert!(invalid.is_err_and(|error| error.to_string().contains("abc")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fail_new_invalid_id() {
+        for id in ["", "a b", "1&platform=WEIDIAN", "１２"] {
+            // -- Exec
+            let actual = Product::new(Marketplace::TaobaoShop, id);
+
+            // -- Check
+            assert!(
+                matches!(actual, Err(Error::InvalidProductId { .. })),
+                "{id:?} should be rejected"
+            );
+        }
+    }
+}
+
+// endregion: --- Tests
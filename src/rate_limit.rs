@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// A token-bucket limit on the requests sent to an upstream host.
+///
+/// Up to `burst` requests go out at once, then requests are spaced to `rate` per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    rate: f64,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Allows `rate` requests per second, with a burst of one request.
+    ///
+    /// # Panics
+    /// Panics if `rate` is not positive.
+    pub fn per_second(rate: f64) -> Self {
+        assert!(rate > 0.0, "rate limit must be positive");
+        Self { rate, burst: 1 }
+    }
+
+    /// Allows `rate` requests per minute, with a burst of one request.
+    pub fn per_minute(rate: f64) -> Self {
+        Self::per_second(rate / 60.0)
+    }
+
+    /// Sets how many requests may go out at once after an idle period.
+    ///
+    /// # Panics
+    /// Panics if `burst` is zero.
+    pub fn burst(mut self, burst: u32) -> Self {
+        assert!(burst > 0, "rate limit burst must be at least 1");
+        self.burst = burst;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Available tokens, negative when requests are queued.
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Per-host token buckets, waited on by network conversions before each request.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    default: Option<RateLimit>,
+    hosts: HashMap<String, RateLimit>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(default: Option<RateLimit>, hosts: HashMap<String, RateLimit>) -> Self {
+        Self {
+            default,
+            hosts,
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// Waits for the turn of a request to `host`. Requests queue in the order they acquire.
+    ///
+    /// A request cancelled while waiting gives its turn back.
+    pub(crate) async fn acquire(&self, host: &str) {
+        let Some(limit) = self.hosts.get(host).or(self.default.as_ref()) else {
+            return;
+        };
+
+        let wait = {
+            let mut buckets = self.lock();
+            let now = Instant::now();
+            let bucket = buckets.entry(host.to_string()).or_insert(Bucket {
+                tokens: limit.burst as f64,
+                updated_at: now,
+            });
+
+            let refilled = now.duration_since(bucket.updated_at).as_secs_f64() * limit.rate;
+            bucket.tokens = (bucket.tokens + refilled).min(limit.burst as f64) - 1.0;
+            bucket.updated_at = now;
+
+            (bucket.tokens < 0.0).then(|| Duration::from_secs_f64(-bucket.tokens / limit.rate))
+        };
+
+        if let Some(wait) = wait {
+            let mut refund = Refund {
+                limiter: Some(self),
+                host,
+            };
+            tokio::time::sleep(wait).await;
+            refund.limiter = None;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Bucket>> {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Gives the token of a request back to its bucket if the request is dropped while waiting.
+struct Refund<'a> {
+    /// `None` once the request got its turn.
+    limiter: Option<&'a RateLimiter>,
+    host: &'a str,
+}
+
+impl Drop for Refund<'_> {
+    fn drop(&mut self) {
+        if let Some(limiter) = self.limiter {
+            if let Some(bucket) = limiter.lock().get_mut(self.host) {
+                bucket.tokens += 1.0;
+            }
+        }
+    }
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_spaces_requests_per_host() {
+        // -- Setup & Fixtures
+        let limiter = RateLimiter::new(
+            Some(RateLimit::per_second(1000.0)),
+            HashMap::from([("m.tb.cn".to_string(), RateLimit::per_second(20.0).burst(2))]),
+        );
+
+        // -- Exec
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire("m.tb.cn").await;
+        }
+        let limited = start.elapsed();
+
+        let start = Instant::now();
+        limiter.acquire("k.youshop10.com").await;
+        let other_host = start.elapsed();
+
+        // -- Check
+        assert!(limited >= Duration::from_millis(45), "took {limited:?}");
+        assert!(
+            other_host < Duration::from_millis(20),
+            "took {other_host:?}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_refunds_cancelled_requests() {
+        // -- Setup & Fixtures
+        let limiter = RateLimiter::new(Some(RateLimit::per_second(10.0)), HashMap::new());
+        limiter.acquire("m.tb.cn").await;
+
+        // -- Exec
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(10), limiter.acquire("m.tb.cn")).await;
+        let start = Instant::now();
+        limiter.acquire("m.tb.cn").await;
+        let waited = start.elapsed();
+
+        // -- Check
+        assert!(cancelled.is_err(), "should have been waiting");
+        assert!(waited < Duration::from_millis(150), "took {waited:?}");
+    }
+}
+
+// endregion: --- Tests
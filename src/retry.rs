@@ -0,0 +1,154 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::Error;
+
+/// Status codes retried by the default [`RetryPolicy`].
+pub const DEFAULT_RETRYABLE_STATUSES: &[u16] = &[408, 429, 500, 502, 503, 504];
+
+/// How network conversions are retried after a transient failure.
+///
+/// Attempt `n` waits `initial_backoff * 2^(n-1)`, capped at `max_backoff`, with a random
+/// jitter of up to half that delay. The default makes 3 attempts and retries timeouts,
+/// connection failures and [`DEFAULT_RETRYABLE_STATUSES`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    retryable_statuses: Vec<u16>,
+    retry_timeouts: bool,
+    retry_connection_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            retryable_statuses: DEFAULT_RETRYABLE_STATUSES.to_vec(),
+            retry_timeouts: true,
+            retry_connection_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy making a single attempt.
+    pub fn none() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// Sets the number of attempts, the first one included.
+    ///
+    /// # Panics
+    /// Panics if `attempts` is zero.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        assert!(attempts > 0, "max attempts must be at least 1");
+        self.max_attempts = attempts;
+        self
+    }
+
+    /// Sets the delay before the first retry and the cap of the exponential backoff.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Sets the upstream HTTP statuses that are retried.
+    pub fn retryable_statuses(mut self, statuses: impl IntoIterator<Item = u16>) -> Self {
+        self.retryable_statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Sets whether timed out requests are retried.
+    pub fn retry_timeouts(mut self, retry: bool) -> Self {
+        self.retry_timeouts = retry;
+        self
+    }
+
+    /// Sets whether requests failing without a response, e.g. on a refused connection, are retried.
+    pub fn retry_connection_errors(mut self, retry: bool) -> Self {
+        self.retry_connection_errors = retry;
+        self
+    }
+
+    pub(crate) fn attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether a failed attempt is worth retrying.
+    pub fn is_retryable(&self, error: &Error) -> bool {
+        match error {
+            Error::UpstreamStatus { status, .. } => self.retryable_statuses.contains(status),
+            Error::Timeout { .. } => self.retry_timeouts,
+            Error::Http { .. } => self.retry_connection_errors,
+            Error::Reqwest(error) => {
+                (error.is_timeout() && self.retry_timeouts)
+                    || (error.is_connect() && self.retry_connection_errors)
+                    || error
+                        .status()
+                        .is_some_and(|status| self.retryable_statuses.contains(&status.as_u16()))
+            }
+            _ => false,
+        }
+    }
+
+    /// The delay before the given retry, `1` being the first one.
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff);
+
+        let jitter = RandomState::new().build_hasher().finish() % 1024;
+        backoff.mul_f64(1.0 - 0.5 * jitter as f64 / 1024.0)
+    }
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_grows_and_is_capped() {
+        // -- Setup & Fixtures
+        let policy =
+            RetryPolicy::default().backoff(Duration::from_millis(100), Duration::from_millis(300));
+
+        // -- Check
+        let first = policy.delay(1);
+        assert!(first > Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let second = policy.delay(2);
+        assert!(second > Duration::from_millis(100) && second <= Duration::from_millis(200));
+        let capped = policy.delay(10);
+        assert!(capped > Duration::from_millis(150) && capped <= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        // -- Setup & Fixtures
+        let policy = RetryPolicy::default().retry_timeouts(false);
+        let status = |status| Error::UpstreamStatus {
+            url: "https://m.tb.cn/h.abc".into(),
+            converter: "mobile_taobao".into(),
+            status,
+        };
+        let timeout = Error::Timeout {
+            url: "https://m.tb.cn/h.abc".into(),
+            converter: "mobile_taobao".into(),
+        };
+
+        // -- Check
+        assert!(policy.is_retryable(&status(503)));
+        assert!(!policy.is_retryable(&status(404)));
+        assert!(!policy.is_retryable(&timeout));
+    }
+}
+
+// endregion: --- Tests
@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Product};
+
+/// How [`crate::Converter::rewrite_text`] writes converted links into the text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RewriteMode {
+    /// Replaces each link with its converted link.
+    #[default]
+    Replace,
+    /// Keeps each link and appends its converted link, e.g. `<link> (<converted>)`.
+    Annotate,
+}
+
+/// A text with its convertible links rewritten.
+#[derive(Debug)]
+pub struct RewrittenText {
+    /// The rewritten text.
+    pub text: String,
+    /// The rewritten links, in the order they appear.
+    pub replacements: Vec<Replacement>,
+    /// Links that failed to convert and were left untouched, with their byte span in the input.
+    pub errors: Vec<(Range<usize>, Error)>,
+}
+
+/// A link rewritten by [`crate::Converter::rewrite_text`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Replacement {
+    /// Byte span of the original link in the input text.
+    pub span: Range<usize>,
+    /// Byte span of the written replacement in the rewritten text.
+    pub output_span: Range<usize>,
+    /// The original link.
+    pub original: String,
+    /// The product the link was converted to.
+    pub product: Product,
+}
+
+pub(crate) fn rewrite(
+    text: &str,
+    mode: RewriteMode,
+    conversions: Vec<(Range<usize>, crate::Result<Product>)>,
+) -> RewrittenText {
+    let mut output = String::with_capacity(text.len());
+    let mut replacements = Vec::new();
+    let mut errors = Vec::new();
+    let mut last_end = 0;
+
+    for (span, converted) in conversions {
+        let product = match converted {
+            Ok(product) => product,
+            Err(e) => {
+                errors.push((span, e));
+                continue;
+            }
+        };
+
+        output.push_str(&text[last_end..span.start]);
+        let start = output.len();
+        match mode {
+            RewriteMode::Replace => output.push_str(product.url().as_str()),
+            RewriteMode::Annotate => {
+                output.push_str(&text[span.clone()]);
+                output.push_str(" (");
+                output.push_str(product.url().as_str());
+                output.push(')');
+            }
+        }
+        last_end = span.end;
+
+        replacements.push(Replacement {
+            original: text[span.clone()].to_string(),
+            span,
+            output_span: start..output.len(),
+            product,
+        });
+    }
+    output.push_str(&text[last_end..]);
+
+    RewrittenText {
+        text: output,
+        replacements,
+        errors,
+    }
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Marketplace;
+
+    const TEXT: &str = "look https://a.example/1 and https://b.example/2!";
+
+    fn conversions() -> Vec<(Range<usize>, crate::Result<Product>)> {
+        vec![
+            (5..24, Product::new(Marketplace::Taobao, "1")),
+            (
+                29..48,
+                Err(Error::UnsupportedHost {
+                    url: "https://b.example/2!".into(),
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_rewrite_replace() {
+        // -- Exec
+        let rewritten = rewrite(TEXT, RewriteMode::Replace, conversions());
+
+        // -- Check
+        assert_eq!(
+            rewritten.text,
+            "look https://item.taobao.com/item.htm?id=1 and https://b.example/2!"
+        );
+        assert_eq!(rewritten.replacements.len(), 1);
+        assert_eq!(rewritten.errors.len(), 1);
+
+        let replacement = &rewritten.replacements[0];
+        assert_eq!(replacement.original, "https://a.example/1");
+        assert_eq!(
+            &rewritten.text[replacement.output_span.clone()],
+            "https://item.taobao.com/item.htm?id=1"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_annotate() {
+        // -- Exec
+        let rewritten = rewrite(TEXT, RewriteMode::Annotate, conversions());
+
+        // -- Check
+        assert_eq!(
+            rewritten.text,
+            "look https://a.example/1 (https://item.taobao.com/item.htm?id=1) and https://b.example/2!"
+        );
+    }
+}
+
+// endregion: --- Tests
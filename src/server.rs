@@ -0,0 +1,234 @@
+use std::future::Future;
+use std::io;
+use std::ops::Range;
+use std::sync::Arc;
+
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{FromRequest, Request, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use url::Url;
+
+use crate::{Converter, Error, Product, Replacement, RewriteMode};
+
+/// The routes of the JSON API, all sharing the converter and so its HTTP client and cache,
+/// if it was built with one.
+///
+/// - `POST /convert` with `{"url", "agent"?}` converts a single link.
+/// - `POST /convert/bulk` with `{"text", "agent"?}` converts every link found in a text.
+/// - `POST /rewrite` with `{"text", "mode"?}` rewrites the links of a text.
+///
+/// Failures answer with `{"error": {"code", "message", ...}}`, see [`Error::code`]. Bodies
+/// that are not valid JSON requests answer with the `invalid_request` code.
+pub fn router(converter: Arc<Converter>) -> Router {
+    Router::new()
+        .route("/convert", post(convert))
+        .route("/convert/bulk", post(convert_bulk))
+        .route("/rewrite", post(rewrite))
+        .with_state(converter)
+}
+
+/// Serves the API on the listener until `shutdown` completes, then waits for the
+/// in-flight requests to finish.
+///
+/// # Errors
+/// Returns the I/O error that stopped the server.
+pub async fn serve(
+    listener: TcpListener,
+    converter: Arc<Converter>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> io::Result<()> {
+    axum::serve(listener, router(converter))
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
+// region:    --- Handlers
+
+#[derive(Debug, Deserialize)]
+struct ConvertRequest {
+    url: String,
+    agent: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConvertResponse {
+    converter: Option<String>,
+    product: Product,
+    /// The product's marketplace URL, or its agent link if one was requested.
+    url: Url,
+}
+
+async fn convert(
+    State(converter): State<Arc<Converter>>,
+    ApiJson(request): ApiJson<ConvertRequest>,
+) -> Result<Json<ConvertResponse>, ApiError> {
+    let url = Url::parse(&request.url).map_err(Error::from)?;
+    let name = converter.converter_for(&url).map(str::to_string);
+
+    let product = converter.convert_one(url).await?;
+    let url = match &request.agent {
+        Some(agent) => converter.agent_link(&product, agent)?,
+        None => product.url().clone(),
+    };
+
+    Ok(Json(ConvertResponse {
+        converter: name,
+        product,
+        url,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct ConvertBulkRequest {
+    text: String,
+    agent: Option<String>,
+}
+
+/// Answers with the `ConversionResult`, whose outputs are products, or agent links if
+/// an agent was requested.
+async fn convert_bulk(
+    State(converter): State<Arc<Converter>>,
+    ApiJson(request): ApiJson<ConvertBulkRequest>,
+) -> Result<Response, ApiError> {
+    let response = match &request.agent {
+        Some(agent) => Json(
+            converter
+                .convert_bulk_to_agent(&request.text, agent)
+                .await?,
+        )
+        .into_response(),
+        None => Json(converter.convert_bulk(&request.text).await?).into_response(),
+    };
+
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize)]
+struct RewriteRequest {
+    text: String,
+    #[serde(default)]
+    mode: RewriteMode,
+}
+
+#[derive(Debug, Serialize)]
+struct RewriteResponse {
+    text: String,
+    replacements: Vec<Replacement>,
+    errors: Vec<RewriteError>,
+}
+
+#[derive(Debug, Serialize)]
+struct RewriteError {
+    span: Range<usize>,
+    error: Error,
+}
+
+async fn rewrite(
+    State(converter): State<Arc<Converter>>,
+    ApiJson(request): ApiJson<RewriteRequest>,
+) -> Json<RewriteResponse> {
+    let rewritten = converter.rewrite_text(&request.text, request.mode).await;
+
+    Json(RewriteResponse {
+        text: rewritten.text,
+        replacements: rewritten.replacements,
+        errors: rewritten
+            .errors
+            .into_iter()
+            .map(|(span, error)| RewriteError { span, error })
+            .collect(),
+    })
+}
+
+// endregion: --- Handlers
+
+// region:    --- Errors
+
+/// A [`Json`] request body, rejected with an [`ApiError`] instead of axum's plain text.
+struct ApiJson<T>(T);
+
+impl<S, T> FromRequest<S> for ApiJson<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state).await?;
+
+        Ok(Self(value))
+    }
+}
+
+/// A failure answered as JSON, with a status matching its kind.
+#[derive(Debug)]
+enum ApiError {
+    Conversion(Error),
+    /// The body is not JSON or not a valid request.
+    InvalidRequest(JsonRejection),
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        Self::Conversion(error)
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::InvalidRequest(rejection)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct Body<E> {
+            error: E,
+        }
+
+        #[derive(Serialize)]
+        struct InvalidRequest {
+            code: &'static str,
+            message: String,
+        }
+
+        match self {
+            ApiError::Conversion(error) => (status(&error), Json(Body { error })).into_response(),
+            ApiError::InvalidRequest(rejection) => {
+                let error = InvalidRequest {
+                    code: "invalid_request",
+                    message: rejection.body_text(),
+                };
+                (rejection.status(), Json(Body { error })).into_response()
+            }
+        }
+    }
+}
+
+fn status(error: &Error) -> StatusCode {
+    match error {
+        Error::InvalidUrl(_) => StatusCode::BAD_REQUEST,
+        Error::UnsupportedHost { .. }
+        | Error::MissingParameter { .. }
+        | Error::UnknownPlatform { .. }
+        | Error::InvalidProductId { .. }
+        | Error::UnknownAgent { .. }
+        | Error::UnsupportedMarketplace { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        Error::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+        Error::UpstreamStatus { .. }
+        | Error::PageParse { .. }
+        | Error::Http { .. }
+        | Error::Reqwest(_) => StatusCode::BAD_GATEWAY,
+        Error::Retried { last_error, .. } => status(last_error),
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+// endregion: --- Errors
@@ -0,0 +1,67 @@
+use link_converter::{BlockingConverter, Marketplace, RewriteMode, BUILTIN_CONVERTERS};
+use url::Url;
+
+type Error = Box<dyn std::error::Error>;
+type Result<T> = core::result::Result<T, Error>; // For tests.
+
+#[test]
+fn test_blocking_converts_offline_links() -> Result<()> {
+    let text = "W2C https://cnfans.com/product?id=758911450758&platform=TAOBAO \
+        and https://detail.1688.com/offer/681296637536.html, not https://crates.io/";
+
+    let converter = BlockingConverter::new()?;
+    assert_eq!(converter.names(), BUILTIN_CONVERTERS);
+
+    let product = converter.convert_one(Url::parse(
+        "https://weidian.com/item.html?itemID=7322752149",
+    )?)?;
+    assert_eq!(product.marketplace(), Marketplace::Weidian);
+
+    let conversion_res = converter.convert_bulk_to_agent(text, "mulebuy")?;
+    let converted: Vec<&str> = conversion_res
+        .successes()
+        .into_iter()
+        .map(Url::as_str)
+        .collect();
+    assert_eq!(
+        converted,
+        [
+            "https://mulebuy.com/product/?shop_type=taobao&id=758911450758",
+            "https://mulebuy.com/product/?shop_type=ali_1688&id=681296637536",
+        ]
+    );
+    assert_eq!(conversion_res.errors().len(), 1);
+
+    let rewritten = converter.rewrite_text(text, RewriteMode::Replace);
+    assert_eq!(
+        rewritten.text,
+        "W2C https://item.taobao.com/item.htm?id=758911450758 \
+        and https://detail.1688.com/offer/681296637536.html, not https://crates.io/"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_blocking_raw_url_to_agent() -> Result<()> {
+    let converter = BlockingConverter::new()?;
+
+    let url = converter.to_agent(
+        Url::parse("https://item.taobao.com/item.htm?spm=a1z10.1-c&id=586064449302")?,
+        "mulebuy",
+    )?;
+    assert_eq!(
+        url.as_str(),
+        "https://mulebuy.com/product/?shop_type=taobao&id=586064449302"
+    );
+
+    let error = converter
+        .to_agent(
+            Url::parse("https://cnfans.com/product?id=586064449302&platform=TAOBAO")?,
+            "mulebuy",
+        )
+        .unwrap_err();
+    assert_eq!(error.code(), "unsupported_host");
+
+    Ok(())
+}
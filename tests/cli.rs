@@ -0,0 +1,114 @@
+#![cfg(feature = "network")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+type Error = Box<dyn std::error::Error>;
+type Result<T> = core::result::Result<T, Error>; // For tests.
+
+const CNFANS: &str = "https://cnfans.com/product?id=758911450758&platform=TAOBAO";
+const TAOBAO: &str = "https://item.taobao.com/item.htm?id=758911450758";
+
+fn run(args: &[&str], stdin: &str) -> Result<Output> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_link-converter"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .ok_or("stdin should be piped")?
+        .write_all(stdin.as_bytes())?;
+
+    Ok(child.wait_with_output()?)
+}
+
+#[test]
+fn test_cli_converts_arguments() -> Result<()> {
+    // -- Exec
+    let output = run(&["--agent", "mulebuy", CNFANS], "")?;
+
+    // -- Check
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://mulebuy.com/product/?shop_type=taobao&id=758911450758\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_converts_stdin_and_reports_failures() -> Result<()> {
+    // -- Exec
+    let output = run(
+        &[],
+        &format!("W2C {CNFANS}\nsee https://www.rust-lang.org/\n"),
+    )?;
+
+    // -- Check
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout)?, format!("{TAOBAO}\n"));
+    assert!(String::from_utf8(output.stderr)?.contains("https://www.rust-lang.org/"));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_writes_csv() -> Result<()> {
+    // -- Exec
+    let output = run(
+        &["--format", "csv", CNFANS, "https://www.rust-lang.org/"],
+        "",
+    )?;
+
+    // -- Check
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        format!(
+            "input,output,converter,error\n\
+            {CNFANS},{TAOBAO},cnfans,\n\
+            https://www.rust-lang.org/,,,no converter supports https://www.rust-lang.org/\n"
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_writes_json() -> Result<()> {
+    // -- Exec
+    let output = run(&["--format", "json"], &format!("W2C {CNFANS}"))?;
+
+    // -- Check
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "entries": [{
+                "input": CNFANS,
+                "span": { "start": 4, "end": 4 + CNFANS.len() },
+                "converter": "cnfans",
+                "output": TAOBAO,
+                "error": null,
+            }]
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_fails_on_usage_error() -> Result<()> {
+    // -- Exec
+    let output = run(&["--nope"], "")?;
+
+    // -- Check
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+
+    Ok(())
+}
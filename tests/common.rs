@@ -1,4 +1,15 @@
-use link_converter::Converter;
+#![cfg(feature = "network")]
+
+use futures::StreamExt;
+use link_converter::converters::{async_trait, LinkConverter, SyncLinkConverter};
+use link_converter::{
+    BulkEntry, ConversionCache, Converter, DiskCache, FetchError, FetchRequest, FetchResponse,
+    Fetcher, Marketplace, MemoryCache, Product, RateLimit, RetryPolicy, RewriteMode, RulesFormat,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
 use url::Url;
 
 type Error = Box<dyn std::error::Error>;
@@ -13,7 +24,8 @@ async fn test_converting_correct_url() -> Result<()> {
     let converted_url = converter.convert_one(url).await?;
 
     assert_eq!(
-        converted_url, "https://weidian.com/item.html?itemID=7301608442",
+        converted_url.to_string(),
+        "https://weidian.com/item.html?itemID=7301608442",
         "url should convert correctly"
     );
 
@@ -28,23 +40,624 @@ async fn test_converting_bulk_convert_url() -> Result<()> {
     let converter = Converter::new()?;
     let conversion_res = converter.convert_bulk(text).await?;
 
-    assert!(conversion_res.successes.len() == 1, "Should convert 1 URL");
     assert!(
-        conversion_res.errors.len() == 2,
+        conversion_res.successes().len() == 1,
+        "Should convert 1 URL"
+    );
+    assert!(
+        conversion_res.errors().len() == 2,
         "Should fail to convert 2 URL's"
     );
 
     Ok(())
 }
 
+#[test]
+fn test_raw_url_to_agent() -> Result<()> {
+    let url = Url::parse("https://weidian.com/item.html?itemID=7322752149")?;
+
+    let converter = Converter::new()?;
+    let agent_url = converter.to_agent(url, "mulebuy")?;
+
+    assert_eq!(
+        agent_url.as_str(),
+        "https://mulebuy.com/product/?shop_type=weidian&id=7322752149"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_converting_agent_to_agent() -> Result<()> {
+    let text = "cnfans: https://cnfans.com/product?id=758911450758&platform=TAOBAO
+        raw: https://detail.1688.com/offer/681296637536.html";
+
+    let converter = Converter::new()?;
+    let conversion_res = converter.convert_bulk_to_agent(text, "mulebuy").await?;
+
+    let converted: Vec<&str> = conversion_res
+        .successes()
+        .into_iter()
+        .map(Url::as_str)
+        .collect();
+    assert_eq!(
+        converted,
+        [
+            "https://mulebuy.com/product/?shop_type=taobao&id=758911450758",
+            "https://mulebuy.com/product/?shop_type=ali_1688&id=681296637536",
+        ]
+    );
+    assert!(conversion_res.errors().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_loaded_rule_overrides_builtin() -> Result<()> {
+    let rules = r#"
+        [[rules]]
+        name = "cnfans"
+        hosts = ["cnfans.com"]
+        path = { prefix = "/item/" }
+        id = { segment = 2 }
+        platform = { segment = 1 }
+        platforms = { taobao = "taobao" }
+        link_template = "https://cnfans.com/item/{platform}/{id}"
+    "#;
+
+    let mut converter = Converter::new()?;
+    converter.load_rules_str(rules, RulesFormat::Toml)?;
+
+    let url = Url::parse("https://cnfans.com/item/taobao/758911450758")?;
+    let product = converter.convert_one(url).await?;
+    assert_eq!(
+        product.to_string(),
+        "https://item.taobao.com/item.htm?id=758911450758"
+    );
+
+    let old_format = Url::parse("https://cnfans.com/product?id=758911450758&platform=TAOBAO")?;
+    assert!(converter.convert_one(old_format).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_converting_raw_url() -> Result<()> {
+    let url = Url::parse("https://item.taobao.com/item.htm?spm=a1z10.1-c&id=586064449302")?;
+
+    let converter = Converter::new()?;
+    let product = converter.convert_one(url).await?;
+
+    assert_eq!(product.marketplace(), Marketplace::Taobao);
+    assert_eq!(product.id(), "586064449302");
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_fail_converting_wrong_url() -> Result<()> {
-    let url = Url::parse("https://item.taobao.com/item.htm?id=586064449302")?;
+    let url = Url::parse("https://www.rust-lang.org/learn")?;
 
     let converter = Converter::new()?;
     let converted_url = converter.convert_one(url.clone()).await;
 
     assert!(converted_url.is_err(), "url conversion should fail");
+    assert_eq!(converted_url.unwrap_err().code(), "unsupported_host");
+
+    Ok(())
+}
+
+/// A converter of `https://{host}/{id}` links, configurable to stand in for network converters.
+#[derive(Debug)]
+struct TestConverter {
+    name: &'static str,
+    host: &'static str,
+    marketplace: Marketplace,
+    delay: Duration,
+    /// Number of first calls failing with HTTP 503.
+    failures: usize,
+    network: bool,
+    calls: Arc<AtomicUsize>,
+}
+
+impl TestConverter {
+    fn new(name: &'static str, host: &'static str) -> Self {
+        Self {
+            name,
+            host,
+            marketplace: Marketplace::Taobao,
+            delay: Duration::ZERO,
+            failures: 0,
+            network: false,
+            calls: Arc::default(),
+        }
+    }
+
+    fn marketplace(mut self, marketplace: Marketplace) -> Self {
+        self.marketplace = marketplace;
+        self
+    }
+
+    fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn failures(mut self, failures: usize) -> Self {
+        self.failures = failures;
+        self
+    }
+
+    fn network(mut self) -> Self {
+        self.network = true;
+        self
+    }
+
+    /// The converter as a [`SyncLinkConverter`], waiting out its delay on the calling thread.
+    fn blocking(self) -> BlockingTestConverter {
+        BlockingTestConverter(self)
+    }
+
+    fn resolve(&self, url: &Url) -> link_converter::Result<Product> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err(link_converter::Error::UpstreamStatus {
+                url: url.to_string(),
+                converter: self.name.to_string(),
+                status: 503,
+            });
+        }
+        let id = url.path().trim_start_matches('/');
+        Product::new(self.marketplace, id)
+    }
+}
+
+#[async_trait]
+impl LinkConverter for TestConverter {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn can_convert(&self, url: &Url) -> bool {
+        url.host_str() == Some(self.host)
+    }
+
+    async fn convert(&self, url: Url) -> link_converter::Result<Product> {
+        tokio::time::sleep(self.delay).await;
+        self.resolve(&url)
+    }
+
+    fn is_network(&self) -> bool {
+        self.network
+    }
+}
+
+#[derive(Debug)]
+struct BlockingTestConverter(TestConverter);
+
+impl SyncLinkConverter for BlockingTestConverter {
+    fn name(&self) -> &str {
+        self.0.name
+    }
+
+    fn can_convert(&self, url: &Url) -> bool {
+        LinkConverter::can_convert(&self.0, url)
+    }
+
+    fn convert(&self, url: Url) -> link_converter::Result<Product> {
+        std::thread::sleep(self.0.delay);
+        self.0.resolve(&url)
+    }
+
+    fn is_network(&self) -> bool {
+        self.0.network
+    }
+}
+
+fn short_link() -> TestConverter {
+    TestConverter::new("short_link", "s.example.com").marketplace(Marketplace::Weidian)
+}
+
+fn slow_short_link() -> TestConverter {
+    TestConverter::new("slow_short_link", "slow.example.com")
+        .delay(Duration::from_millis(200))
+        .network()
+}
+
+fn counting_short_link() -> TestConverter {
+    TestConverter::new("counting_short_link", "count.example.com").network()
+}
+
+#[tokio::test]
+async fn test_builder_registers_custom_converter() -> Result<()> {
+    let converter = Converter::builder()
+        .enable_only(["cssbuy"])
+        .register(short_link())
+        .build()?;
+
+    assert_eq!(converter.names(), ["cssbuy", "short_link"]);
+
+    let product = converter
+        .convert_one(Url::parse("https://s.example.com/7322752149")?)
+        .await?;
+    assert_eq!(
+        product.to_string(),
+        "https://weidian.com/item.html?itemID=7322752149"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_bulk_converts_network_links_concurrently() -> Result<()> {
+    let text = "https://slow.example.com/1 https://slow.example.com/2
+        https://cnfans.com/product?id=3&platform=TAOBAO
+        https://slow.example.com/4 https://slow.example.com/5";
+
+    let converter = Converter::builder()
+        .register(slow_short_link())
+        .concurrency(4)
+        .build()?;
+
+    let start = Instant::now();
+    let conversion_res = converter.convert_bulk(text).await?;
+    let elapsed = start.elapsed();
+
+    let ids: Vec<&str> = conversion_res
+        .successes()
+        .into_iter()
+        .map(|p| p.id())
+        .collect();
+    assert_eq!(ids, ["1", "2", "3", "4", "5"], "should keep input order");
+    assert!(
+        elapsed < Duration::from_millis(600),
+        "should not convert sequentially, took {elapsed:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_yields_ready_conversions_first() -> Result<()> {
+    let text = "https://slow.example.com/1 https://cnfans.com/product?id=2&platform=TAOBAO";
+
+    let converter = Converter::builder().register(slow_short_link()).build()?;
+
+    let entries: Vec<BulkEntry> = converter.convert_stream(text).collect().await;
+
+    let urls: Vec<&str> = entries.iter().map(|entry| entry.input.as_str()).collect();
+    assert_eq!(
+        urls,
+        [
+            "https://cnfans.com/product?id=2&platform=TAOBAO",
+            "https://slow.example.com/1"
+        ]
+    );
+    assert!(entries.iter().all(|entry| entry.outcome.is_ok()));
+    assert_eq!(entries[1].converter.as_deref(), Some("slow_short_link"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rewrite_text_keeps_message() -> Result<()> {
+    let text =
+        "W2C? https://cnfans.com/product?id=758911450758&platform=TAOBAO thanks https://crates.io/";
+
+    let converter = Converter::new()?;
+    let rewritten = converter.rewrite_text(text, RewriteMode::Replace).await;
+
+    assert_eq!(
+        rewritten.text,
+        "W2C? https://item.taobao.com/item.htm?id=758911450758 thanks https://crates.io/"
+    );
+    assert_eq!(rewritten.replacements[0].span, 5..63);
+    assert_eq!(rewritten.errors.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bulk_groups_same_product() -> Result<()> {
+    let text = "https://cnfans.com/product?id=758911450758&platform=TAOBAO \
+        https://mulebuy.com/product/?shop_type=taobao&id=758911450758 \
+        https://count.example.com/758911450758 https://count.example.com/758911450758 \
+        https://weidian.com/item.html?itemID=7322752149";
+
+    let counter = counting_short_link();
+    let calls = counter.calls.clone();
+    let converter = Converter::builder().register(counter).build()?;
+
+    let conversion_res = converter.convert_bulk(text).await?;
+    let groups = conversion_res.group_by_product();
+
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        1,
+        "identical links convert once"
+    );
+    assert_eq!(conversion_res.entries.len(), 5);
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].product.id(), "758911450758");
+    let converters: Vec<Option<&str>> = groups[0]
+        .entries
+        .iter()
+        .map(|entry| entry.converter.as_deref())
+        .collect();
+    assert_eq!(
+        converters,
+        [
+            Some("cnfans"),
+            Some("mulebuy"),
+            Some("counting_short_link"),
+            Some("counting_short_link")
+        ]
+    );
+    assert_eq!(groups[1].entries.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_skips_repeated_network_calls() -> Result<()> {
+    let url = Url::parse("https://count.example.com/758911450758")?;
+
+    let counter = counting_short_link();
+    let calls = counter.calls.clone();
+    let converter = Converter::builder()
+        .register(counter)
+        .cache(MemoryCache::new(16))
+        .build()?;
+
+    let first = converter.convert_one(url.clone()).await?;
+    let second = converter.convert_one(url).await?;
+
+    assert_eq!(first, second);
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        1,
+        "second call should be cached"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retries_transient_failures() -> Result<()> {
+    let url = Url::parse("https://flaky.example.com/758911450758")?;
+    let policy = RetryPolicy::default().backoff(Duration::from_millis(1), Duration::from_millis(5));
+
+    let converter = Converter::builder()
+        .register(
+            TestConverter::new("flaky_short_link", "flaky.example.com")
+                .failures(2)
+                .network(),
+        )
+        .retry(policy.clone().max_attempts(3))
+        .build()?;
+    let product = converter.convert_one(url.clone()).await?;
+    assert_eq!(product.id(), "758911450758");
+
+    let converter = Converter::builder()
+        .register(
+            TestConverter::new("flaky_short_link", "flaky.example.com")
+                .failures(2)
+                .network(),
+        )
+        .retry(policy.max_attempts(2))
+        .build()?;
+    let error = converter.convert_one(url).await.unwrap_err();
+    assert_eq!(error.code(), "retried");
+    assert_eq!(error.attempts(), Some(2));
+    assert_eq!(error.converter(), Some("flaky_short_link"));
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_timeouts_and_bulk_deadline() -> Result<()> {
+    let text = "https://slow.example.com/1 https://cnfans.com/product?id=2&platform=TAOBAO";
+
+    let converter = Converter::builder()
+        .register(slow_short_link())
+        .converter_timeout("slow_short_link", Duration::from_millis(50))
+        .retry(RetryPolicy::none())
+        .build()?;
+    let error = converter
+        .convert_one(Url::parse("https://slow.example.com/1")?)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "timeout");
+
+    let converter = Converter::builder()
+        .register(slow_short_link())
+        .bulk_deadline(Duration::from_millis(50))
+        .build()?;
+    let start = Instant::now();
+    let conversion_res = converter.convert_bulk(text).await?;
+    assert!(start.elapsed() < Duration::from_millis(150));
+    assert_eq!(conversion_res.successes().len(), 1);
+    let errors = conversion_res.errors();
+    assert_eq!(errors[0].0, "https://slow.example.com/1");
+    assert_eq!(errors[0].1.code(), "timeout");
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_rate_limit_queues_requests_per_host() -> Result<()> {
+    let text =
+        "https://count.example.com/1 https://count.example.com/2 https://count.example.com/3";
+
+    let converter = Converter::builder()
+        .register(counting_short_link())
+        .host_rate_limit("count.example.com", RateLimit::per_second(20.0))
+        .build()?;
+
+    let start = Instant::now();
+    let conversion_res = converter.convert_bulk(text).await?;
+
+    assert_eq!(
+        conversion_res.successes().len(),
+        3,
+        "should queue, not fail"
+    );
+    assert!(start.elapsed() >= Duration::from_millis(90));
+
+    Ok(())
+}
+
+#[test]
+fn test_blocking_converter_uses_sync_converters() -> Result<()> {
+    let converter = Converter::builder()
+        .register_sync(short_link().blocking())
+        .build_blocking()?;
+
+    let product = converter.convert_one(Url::parse("https://s.example.com/7322752149")?)?;
+    assert_eq!(product.marketplace(), Marketplace::Weidian);
+
+    let error = Converter::builder()
+        .register(short_link())
+        .build_blocking()
+        .unwrap_err();
+    assert_eq!(error.code(), "async_only_converter");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_network_converters_do_not_block_timeouts() -> Result<()> {
+    let converter = Converter::builder()
+        .register_sync(
+            slow_short_link()
+                .delay(Duration::from_millis(500))
+                .blocking(),
+        )
+        .converter_timeout("slow_short_link", Duration::from_millis(50))
+        .retry(RetryPolicy::none())
+        .build()?;
+
+    let error = converter
+        .convert_one(Url::parse("https://slow.example.com/1")?)
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error.code(),
+        "timeout",
+        "the blocking sleep should not finish first"
+    );
+
+    Ok(())
+}
+
+/// Answers with recorded share pages, after answering the first calls with `statuses`.
+#[derive(Debug, Default)]
+struct RecordedFetcher {
+    statuses: Vec<u16>,
+    requests: Arc<AtomicUsize>,
+}
+
+impl RecordedFetcher {
+    fn failing_with(statuses: impl Into<Vec<u16>>) -> Self {
+        Self {
+            statuses: statuses.into(),
+            requests: Arc::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl Fetcher for RecordedFetcher {
+    async fn fetch(
+        &self,
+        request: FetchRequest,
+    ) -> core::result::Result<FetchResponse, FetchError> {
+        let request_index = self.requests.fetch_add(1, Ordering::SeqCst);
+        let status = self.statuses.get(request_index).copied().unwrap_or(200);
+
+        Ok(FetchResponse {
+            status,
+            url: request.url,
+            headers: Vec::new(),
+            body: b"<a href=\"https://shop247709762.world.taobao.com/\">".to_vec(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_network_converters_use_fetcher() -> Result<()> {
+    let fetcher = RecordedFetcher::failing_with([503]);
+    let requests = fetcher.requests.clone();
+    let converter = Converter::builder()
+        .fetcher(fetcher)
+        .retry(RetryPolicy::default().backoff(Duration::from_millis(1), Duration::from_millis(5)))
+        .build()?;
+
+    let product = converter
+        .convert_one(Url::parse("https://m.tb.cn/h.TTHL3ZZKsh88JtB")?)
+        .await?;
+
+    assert_eq!(
+        product.to_string(),
+        "https://shop247709762.world.taobao.com/"
+    );
+    assert_eq!(requests.load(Ordering::SeqCst), 2, "503 should be retried");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fail_after_retry_keeps_attempts() -> Result<()> {
+    let converter = Converter::builder()
+        .fetcher(RecordedFetcher::failing_with([503, 404]))
+        .retry(RetryPolicy::default().backoff(Duration::from_millis(1), Duration::from_millis(5)))
+        .build()?;
+
+    let error = converter
+        .convert_one(Url::parse("https://m.tb.cn/h.TTHL3ZZKsh88JtB")?)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.attempts(), Some(2), "the 404 should end the retries");
+    let link_converter::Error::Retried { last_error, .. } = error else {
+        return Err(format!("expected a retried error, got {error:?}").into());
+    };
+    assert!(
+        matches!(
+            *last_error,
+            link_converter::Error::UpstreamStatus { status: 404, .. }
+        ),
+        "{last_error:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_disk_cache_shared_with_converter() -> Result<()> {
+    let path = std::env::temp_dir().join(format!(
+        "link-converter-{}-converter.jsonl",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let cache = Arc::new(DiskCache::open(&path)?);
+    let converter = Converter::builder()
+        .register(counting_short_link())
+        .cache(cache.clone())
+        .build()?;
+
+    converter
+        .convert_one(Url::parse("https://count.example.com/758911450758")?)
+        .await?;
+    cache.flush()?;
+
+    let reopened = DiskCache::open(&path)?;
+    assert_eq!(reopened.len(), 1);
+    let product = reopened
+        .get("https://count.example.com/758911450758")
+        .ok_or("should be stored")??;
+    assert_eq!(product.id(), "758911450758");
 
+    std::fs::remove_file(path)?;
     Ok(())
 }
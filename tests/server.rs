@@ -0,0 +1,162 @@
+#![cfg(feature = "server")]
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use link_converter::{server, Converter};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+type Error = Box<dyn std::error::Error>;
+type Result<T> = core::result::Result<T, Error>; // For tests.
+
+const CNFANS: &str = "https://cnfans.com/product?id=758911450758&platform=TAOBAO";
+const TAOBAO: &str = "https://item.taobao.com/item.htm?id=758911450758";
+
+struct TestServer {
+    addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<std::io::Result<()>>,
+}
+
+async fn start() -> Result<TestServer> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (shutdown, signal) = oneshot::channel();
+    let converter = Arc::new(Converter::new()?);
+    let handle = tokio::spawn(server::serve(listener, converter, async {
+        _ = signal.await;
+    }));
+
+    Ok(TestServer {
+        addr,
+        shutdown,
+        handle,
+    })
+}
+
+async fn post(addr: SocketAddr, path: &str, body: Value) -> Result<(u16, Value)> {
+    let response = reqwest::Client::new()
+        .post(format!("http://{addr}{path}"))
+        .json(&body)
+        .send()
+        .await?;
+
+    Ok((response.status().as_u16(), response.json().await?))
+}
+
+#[tokio::test]
+async fn test_server_converts_one_and_bulk() -> Result<()> {
+    // -- Setup & Fixtures
+    let server = start().await?;
+
+    // -- Exec
+    let (one_status, one) = post(server.addr, "/convert", json!({ "url": CNFANS })).await?;
+    let (bulk_status, bulk) = post(
+        server.addr,
+        "/convert/bulk",
+        json!({ "text": format!("W2C {CNFANS}"), "agent": "mulebuy" }),
+    )
+    .await?;
+
+    // -- Check
+    assert_eq!(one_status, 200);
+    assert_eq!(
+        one,
+        json!({
+            "converter": "cnfans",
+            "product": { "marketplace": "taobao", "id": "758911450758", "url": TAOBAO },
+            "url": TAOBAO,
+        })
+    );
+    assert_eq!(bulk_status, 200);
+    assert_eq!(
+        bulk["entries"][0]["output"],
+        "https://mulebuy.com/product/?shop_type=taobao&id=758911450758"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_rewrites_text() -> Result<()> {
+    // -- Setup & Fixtures
+    let server = start().await?;
+
+    // -- Exec
+    let (status, rewritten) = post(
+        server.addr,
+        "/rewrite",
+        json!({ "text": format!("W2C {CNFANS} or https://www.rust-lang.org/") }),
+    )
+    .await?;
+
+    // -- Check
+    assert_eq!(status, 200);
+    assert_eq!(
+        rewritten["text"],
+        format!("W2C {TAOBAO} or https://www.rust-lang.org/")
+    );
+    assert_eq!(rewritten["errors"][0]["error"]["code"], "unsupported_host");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_answers_errors_and_shuts_down() -> Result<()> {
+    // -- Setup & Fixtures
+    let server = start().await?;
+
+    // -- Exec
+    let (unsupported_status, unsupported) = post(
+        server.addr,
+        "/convert",
+        json!({ "url": "https://www.rust-lang.org/" }),
+    )
+    .await?;
+    let (invalid_status, _) = post(server.addr, "/convert", json!({ "url": "nope" })).await?;
+
+    // -- Check
+    assert_eq!(unsupported_status, 422);
+    assert_eq!(unsupported["error"]["code"], "unsupported_host");
+    assert_eq!(invalid_status, 400);
+
+    server
+        .shutdown
+        .send(())
+        .map_err(|_| "server should be running")?;
+    server.handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_answers_invalid_requests_as_json() -> Result<()> {
+    // -- Setup & Fixtures
+    let server = start().await?;
+
+    // -- Exec
+    let (missing_status, missing) = post(server.addr, "/convert", json!({})).await?;
+    let (mode_status, mode) = post(
+        server.addr,
+        "/rewrite",
+        json!({ "text": CNFANS, "mode": "bogus" }),
+    )
+    .await?;
+
+    // -- Check
+    assert_eq!(missing_status, 422);
+    assert_eq!(missing["error"]["code"], "invalid_request");
+    assert!(
+        missing["error"]["message"]
+            .as_str()
+            .is_some_and(|message| message.contains("url")),
+        "{missing}"
+    );
+    assert_eq!(mode_status, 422);
+    assert_eq!(mode["error"]["code"], "invalid_request");
+
+    Ok(())
+}